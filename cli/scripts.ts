@@ -85,6 +85,7 @@ export const configProject = async () => {
   const migrationWallet = new PublicKey("DQ8fi6tyN9MPD5bpSpUXxKd9FVRY2WcnoniVEgs6StEW");
   // Create a dummy config object to pass as argument.
   const newConfig = {
+    version: 0, // stamped by the program
     authority: payer.publicKey, // from node wallet
     migrationAuthority: payer.publicKey, // from node wallet
     teamWallet: teamWallet,
@@ -101,14 +102,50 @@ export const configProject = async () => {
       range: { min: new BN(1_000_000_000), max: new BN(1_000_000_000) }, // min and max tokens allowed for [virtual token reserves]
     },
     tokenDecimalsConfig: { range: { min: 6, max: 6 } }, // max allowd decimals for a coin
+    graduationTargetConfig: {
+      range: { min: new BN(1_000_000_000), max: new BN(500_000_000_000) }, // allowed per-launch graduation targets in lamports
+    },
     initialVirtualTokenReservesConfig: new BN(TEST_INITIAL_VIRTUAL_TOKEN_RESERVES),
     initialVirtualSolReservesConfig: new BN(TEST_INITIAL_VIRTUAL_SOL_RESERVES),
     initialRealTokenReservesConfig: new BN(TEST_INITIAL_REAL_TOKEN_RESERVES),
     initialRaydiumTokenReserves: new BN(TEST_INITIAL_RAYDIUM_TOKEN_RESERVES),
     initialRaydiumSolAmount: new BN(TEST_INITIAL_RAYDIUM_SOL_AMOUNT),
+    snipeTaxSlots: new BN(0), // slots after launch during which buys pay the snipe tax
+    snipeTaxBps: 0, // extra buy fee for snipers, in basis points
+    feeTiers: [], // e.g [{ minLamports: new BN(10_000_000_000), feeBps: 50 }], empty uses the flat platform fees
+    emitReserves: false, // emit a ReserveSnapshot event after every trade
+    minInitialBuyLamports: new BN(0), // minimum creator dev-buy, 0 keeps it optional
+    minSlotsBetweenLaunches: new BN(0), // per-creator launch throttle, 0 disables it
+    maxBuyFractionBps: 0, // max share of the real token reserves per buy, 0 disables it
+    sellFeeFromReserves: false, // take the sell fee from the seller's proceeds
+    maxSolReserves: new BN(0), // hard cap on real lamports per curve, 0 disables it
+    enforceBlocklist: false, // reject trades from blocked wallets
+    requireSlippageProtection: false, // reject trades with a zero minimum output
+    feeOverrideConfig: { range: { min: 0, max: 100 } }, // allowed per-curve fee overrides in bps
+    buysEnabled: true,
+    sellsEnabled: true,
+    maxBootstrapLiquidityBps: 0, // max share of the supply set aside for early dex liquidity
+    creatorMinHoldBps: 0, // share of the dev-buy the creator keeps until graduation
+    allowedUriPrefixes: [], // e.g ["https://", "ipfs://", "ar://"], empty accepts any metadata uri
+    lockedLpAmount: new BN(0), // LP tokens burned at migration, e.g 1000 like the uniswap minimum liquidity
+    sellReflectionBps: 0, // share of sell proceeds left in the curve reserves for remaining holders
+    maxPendingMigrations: new BN(0), // completed but unmigrated curves before graduating buys pause, 0 disables it
+    pendingMigrations: new BN(0), // maintained by the program, ignored here
+    activeCurves: new BN(0), // maintained by the program, ignored here
+    lossSellGrace: false, // track cost basis and discount sells below the average entry
+    lossSellFeeBps: 0, // sell fee for those exits
+    graduationWindowBps: 9000, // curve progress from which near-graduation sells are capped
+    sellLimitNearGraduationBps: 0, // max share of the sol reserves per sell in that window, 0 disables it
+    maxHolders: new BN(0), // per-curve cap on wallets that ever bought, 0 disables it
+    migrationSurplus: { addToPool: {} }, // or { returnToTeam: {} } / { burn: {} } for sol beyond initialRaydiumSolAmount
+    minBuyLamports: new BN(0), // smallest buy, the graduating buy is exempt, 0 disables it
+    migrationDelaySeconds: new BN(0), // cooldown between graduation and migrate, 0 migrates right away
+    feeMode: 0, // 0 sends the team fee every trade, 1 accrues it in the vault for claimTeamFees
+    maxFeeBasisPoints: 0, // trading fee ceiling, can only be lowered once set, 0 leaves it unset
+    requireVerifiedCreator: false, // force a verified creator in every launch's metadata
 
     curveLimit: new BN(/*1_416_000_000*/85_000_000_000), //  Example limit: 85 SOL (lamports to complete the bonding curve)
-    initialized: false,
+    initialized: true,
   };
 
   const tx = await createConfigTx(
@@ -132,6 +169,18 @@ export const createBondingCurve = async () => {
     TEST_DECIMALS, // 6
     TEST_TOKEN_SUPPLY, // 1B
     TEST_VIRTUAL_RESERVES, // 20 SOL
+    null, // graduate at the config curve limit
+    null, // no creator dev-buy
+    null, // so no dev-buy slippage guard
+    null, // whole supply backs the curve
+    true, // verified creator in the metadata
+    null, // no royalty
+    null, // global trade fees
+    null, // no bootstrap liquidity
+    null, // immutable metadata
+    null, // graduate to raydium
+    null, // not part of a collection
+    null, // fresh mint address
 
     //  metadata
     TEST_NAME,
@@ -158,6 +207,11 @@ export const swap = async (
     token,
     amount,
     style,
+    0, // no slippage bound
+    false,
+    null, // no deadline
+    false,
+    null, // exact-input styles only
     solConnection,
     program
   );
@@ -174,6 +228,7 @@ export const migrate = async (token: PublicKey) => {
     payer.publicKey,
     token,
     market,
+    false, // deposit the whole curve balance
     solConnection,
     program
   );
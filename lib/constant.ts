@@ -3,6 +3,14 @@ import { Cluster, PublicKey } from "@solana/web3.js";
 
 export const SEED_CONFIG = "config";
 export const SEED_BONDING_CURVE = "bonding_curve";
+export const SEED_BLOCKLIST = "blocklist";
+export const SEED_COST_BASIS = "cost_basis";
+export const SEED_BOOTSTRAP_LIQUIDITY = "bootstrap_liquidity";
+export const SEED_CURVE_VAULT = "curve_vault";
+
+export const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
 
 export const TEST_NAME = "sc4m";
 export const TEST_SYMBOL = "SCAM";
@@ -14,11 +14,16 @@ import {
   ammProgram,
   feeDestination,
   marketProgram,
+  SEED_BLOCKLIST,
   SEED_BONDING_CURVE,
+  SEED_BOOTSTRAP_LIQUIDITY,
   SEED_CONFIG,
+  SEED_COST_BASIS,
+  TOKEN_METADATA_PROGRAM_ID,
 } from "./constant";
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
   NATIVE_MINT,
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
@@ -38,8 +43,13 @@ export const createConfigTx = async (
 
   console.log("configPda: ", configPda.toBase58());
 
-  const tx = await program.methods
-    .configure(newConfig) // config
+  //  the first config is created with initializeConfig, configure updates it in place.
+  //  configs stored before the version field have to go through migrateConfig first
+  const configInfo = await connection.getAccountInfo(configPda);
+  const tx = await (configInfo === null
+    ? program.methods.initializeConfig(newConfig)
+    : program.methods.configure(newConfig)
+  )
     .accounts({
       payer: admin, // creator
     })
@@ -56,6 +66,18 @@ export const createBondingCurveTx = async (
   decimal: number,
   supply: number, // token supply (e.g 1B)
   reserve: number, // sol reserves (e.g 20 SOL)
+  graduationTarget: number | null, // lamports to complete the curve (defaults to the config curve limit)
+  initialBuy: number | null, // creator dev-buy in lamports
+  minTokensOut: number | null, // dev-buy slippage guard
+  teamAllocationBps: number | null, // share of the supply minted to the team wallet
+  verifyCreator: boolean, // list the creator as a verified metadata creator
+  sellerFeeBasisPoints: number | null, // metadata royalty, defaults to 0
+  feeOverrideBps: number | null, // per-curve trade fee, defaults to the global fee
+  bootstrapLiquidityBps: number | null, // share of the supply set aside for early dex liquidity
+  isMutable: boolean | null, // metadata mutability, defaults to immutable
+  migrationTarget: number | null, // graduation dex (0 = raydium), defaults to raydium
+  collectionMint: PublicKey | null, // verified collection, `user` has to be its update authority
+  mint: Keypair | null, // reuse to retry a failed launch at the same address, generated when null
   name: string,
   symbol: string,
   uri: string,
@@ -65,27 +87,77 @@ export const createBondingCurveTx = async (
   connection: Connection,
   program: Program<PumpRaydium>
 ) => {
-  const tokenKp = Keypair.generate();
+  //  a failed launch leaves no state behind, so the same keypair can be sent again
+  const tokenKp = mint ?? Keypair.generate();
 
   console.log("token address: ", tokenKp.publicKey.toBase58());
 
+  const bootstrapLiquidity = PublicKey.findProgramAddressSync(
+    [Buffer.from(SEED_BOOTSTRAP_LIQUIDITY), tokenKp.publicKey.toBytes()],
+    program.programId
+  )[0];
+
+  const collectionPda = (...seeds: Buffer[]) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        collectionMint.toBuffer(),
+        ...seeds,
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    )[0];
+
   // Send the transaction to launch a token
   const tx = await program.methods
-    .createBondingCurve(
+    .createBondingCurve({
       //  launch config
-      decimal,
-      new BN(supply),
-      new BN(reserve),
+      decimals: decimal,
+      tokenSupply: new BN(supply),
+      reserveLamport: new BN(reserve),
+      graduationTarget:
+        graduationTarget === null ? null : new BN(graduationTarget),
+      initialBuyLamports: initialBuy === null ? null : new BN(initialBuy),
+      minTokensOut: minTokensOut === null ? null : new BN(minTokensOut),
+      teamAllocationBps,
+      verifyCreator,
+      sellerFeeBasisPoints,
+      feeOverrideBps,
+      bootstrapLiquidityBps,
+      isMutable,
+      migrationTarget,
 
       //  metadata
       name,
       symbol,
-      uri
-    )
+      uri,
+    })
     .accounts({
       creator: user,
       token: tokenKp.publicKey,
       teamWallet,
+      creatorTokenAccount:
+        initialBuy === null
+          ? null
+          : getAssociatedTokenAddressSync(tokenKp.publicKey, user),
+      teamTokenAccount:
+        teamAllocationBps === null
+          ? null
+          : getAssociatedTokenAddressSync(tokenKp.publicKey, teamWallet, true),
+      bootstrapLiquidity: bootstrapLiquidityBps === null ? null : bootstrapLiquidity,
+      bootstrapTokenAccount:
+        bootstrapLiquidityBps === null
+          ? null
+          : getAssociatedTokenAddressSync(
+              tokenKp.publicKey,
+              bootstrapLiquidity,
+              true
+            ),
+      collectionMint,
+      collectionMetadata: collectionMint === null ? null : collectionPda(),
+      collectionMasterEdition:
+        collectionMint === null ? null : collectionPda(Buffer.from("edition")),
+      collectionAuthority: collectionMint === null ? null : user,
     })
     .transaction();
 
@@ -103,6 +175,11 @@ export const swapTx = async (
 
   amount: number,
   style: number,
+  minimumReceiveAmount: number, // tokens for buys, lamports for sells
+  clampToSlippage: boolean, // shrink the trade instead of reverting on slippage
+  deadline: number | null, // unix timestamp the trade has to land by
+  payWithWsol: boolean, // pay buys from the user's WSOL account, closes it and returns the rest as SOL
+  maxInput: number | null, // lamports cap for style 2 (exact-output buys), fees included
 
   connection: Connection,
   program: Program<PumpRaydium>
@@ -114,11 +191,34 @@ export const swapTx = async (
   const configAccount = await program.account.config.fetch(configPda);
 
   const tx = await program.methods
-    .swap(new BN(amount), style, new BN(amount))
+    .swap(
+      new BN(amount),
+      style,
+      new BN(minimumReceiveAmount),
+      payWithWsol,
+      clampToSlippage,
+      deadline === null ? null : new BN(deadline),
+      maxInput === null ? null : new BN(maxInput)
+    )
     .accounts({
       teamWallet: configAccount.teamWallet,
       user,
       tokenMint: token,
+      userWsolAccount: payWithWsol
+        ? getAssociatedTokenAddressSync(NATIVE_MINT, user)
+        : null,
+      blockedWallet: PublicKey.findProgramAddressSync(
+        [Buffer.from(SEED_BLOCKLIST), user.toBytes()],
+        program.programId
+      )[0],
+      // only tracked while the config enables the loss sell grace or a holder cap
+      costBasis:
+        configAccount.lossSellGrace || !configAccount.maxHolders.isZero()
+          ? PublicKey.findProgramAddressSync(
+              [Buffer.from(SEED_COST_BASIS), token.toBytes(), user.toBytes()],
+              program.programId
+            )[0]
+          : null,
     })
     .transaction();
 
@@ -128,10 +228,150 @@ export const swapTx = async (
   return tx;
 };
 
+//  sells the user's whole token balance as it is when the tx lands
+export const sellAllTx = async (
+  user: PublicKey,
+  token: PublicKey,
+
+  minimumReceiveAmount: number, // lamports
+  deadline: number | null, // unix timestamp the trade has to land by
+
+  connection: Connection,
+  program: Program<PumpRaydium>
+) => {
+  const [configPda, _] = PublicKey.findProgramAddressSync(
+    [Buffer.from(SEED_CONFIG)],
+    program.programId
+  );
+  const configAccount = await program.account.config.fetch(configPda);
+
+  const tx = await program.methods
+    .sellAll(
+      new BN(minimumReceiveAmount),
+      deadline === null ? null : new BN(deadline)
+    )
+    .accounts({
+      teamWallet: configAccount.teamWallet,
+      user,
+      tokenMint: token,
+      userWsolAccount: null,
+      blockedWallet: PublicKey.findProgramAddressSync(
+        [Buffer.from(SEED_BLOCKLIST), user.toBytes()],
+        program.programId
+      )[0],
+      // only tracked while the config enables the loss sell grace or a holder cap
+      costBasis:
+        configAccount.lossSellGrace || !configAccount.maxHolders.isZero()
+          ? PublicKey.findProgramAddressSync(
+              [Buffer.from(SEED_COST_BASIS), token.toBytes(), user.toBytes()],
+              program.programId
+            )[0]
+          : null,
+    })
+    .transaction();
+
+  tx.feePayer = user;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+//  buys exactly what the curve needs to graduate, `maxLamports` caps the spend incl. fees
+export const completeBuyTx = async (
+  user: PublicKey,
+  token: PublicKey,
+  maxLamports: number,
+
+  connection: Connection,
+  program: Program<PumpRaydium>
+) => {
+  const [configPda, _] = PublicKey.findProgramAddressSync(
+    [Buffer.from(SEED_CONFIG)],
+    program.programId
+  );
+  const configAccount = await program.account.config.fetch(configPda);
+
+  const tx = await program.methods
+    .completeBuy(new BN(maxLamports))
+    .accounts({
+      teamWallet: configAccount.teamWallet,
+      user,
+      tokenMint: token,
+      blockedWallet: PublicKey.findProgramAddressSync(
+        [Buffer.from(SEED_BLOCKLIST), user.toBytes()],
+        program.programId
+      )[0],
+      // only tracked while the config enables the loss sell grace or a holder cap
+      costBasis:
+        configAccount.lossSellGrace || !configAccount.maxHolders.isZero()
+          ? PublicKey.findProgramAddressSync(
+              [Buffer.from(SEED_COST_BASIS), token.toBytes(), user.toBytes()],
+              program.programId
+            )[0]
+          : null,
+    })
+    .transaction();
+
+  tx.feePayer = user;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+//  moves a curve launched on the shared global vault onto its own vault
+export const migrateCurveVaultTx = async (
+  payer: PublicKey,
+  token: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpRaydium>
+) => {
+  const tx = await program.methods
+    .migrateCurveVault()
+    .accounts({
+      payer,
+      tokenMint: token,
+    })
+    .transaction();
+
+  tx.feePayer = payer;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
+export const claimTeamFeesTx = async (
+  payer: PublicKey,
+  token: PublicKey,
+
+  connection: Connection,
+  program: Program<PumpRaydium>
+) => {
+  const configPda = PublicKey.findProgramAddressSync(
+    [Buffer.from(SEED_CONFIG)],
+    program.programId
+  )[0];
+  const configAccount = await program.account.config.fetch(configPda);
+
+  const tx = await program.methods
+    .claimTeamFees()
+    .accounts({
+      teamWallet: configAccount.teamWallet,
+      tokenMint: token,
+    })
+    .transaction();
+
+  tx.feePayer = payer;
+  tx.recentBlockhash = (await connection.getLatestBlockhash()).blockhash;
+
+  return tx;
+};
+
 export const migrateTx = async (
   payer: PublicKey,
   token: PublicKey,
   market: PublicKey,
+  burnUnsold: boolean, // burn the tokens the pool doesn't need
 
   connection: Connection,
   program: Program<PumpRaydium>
@@ -165,7 +405,7 @@ export const migrateTx = async (
     .add(ComputeBudgetProgram.setComputeUnitLimit({ units: 500_000 }))
     .add(
       await program.methods
-        .migrate(nonce)
+        .migrate(nonce, burnUnsold)
         .accounts({
           teamWallet: configAccount.teamWallet,
           ammProgram,
@@ -190,3 +430,27 @@ export const migrateTx = async (
 };
 
 
+
+export const getConfig = async (program: Program<PumpRaydium>) => {
+  //  simulated, the config comes back as the instruction's return data
+  return await program.methods.getConfig().view();
+};
+
+export const getPosition = async (
+  user: PublicKey,
+  token: PublicKey,
+  program: Program<PumpRaydium>
+) => {
+  //  simulated, the unrealized P/L in lamports comes back as the return data
+  return await program.methods
+    .getPosition()
+    .accounts({
+      tokenMint: token,
+      user,
+      costBasis: PublicKey.findProgramAddressSync(
+        [Buffer.from(SEED_COST_BASIS), token.toBytes(), user.toBytes()],
+        program.programId
+      )[0],
+    })
+    .view();
+};
@@ -1,8 +1,19 @@
+use anchor_lang::solana_program::{pubkey, pubkey::Pubkey};
+
 pub const CONFIG: &str = "config";
 pub const GLOBAL: &str = "global";
+pub const CURVE_VAULT: &str = "curve_vault"; //  per-curve vault holding only that curve's SOL
 pub const BONDING_CURVE: &str = "bonding_curve";
+pub const BONDING_CURVE_IS_COMPLETED_OFFSET: usize = 8; // byte offset of `is_completed`, right after the discriminator
+pub const CREATOR_STATS: &str = "creator_stats";
+pub const BLOCKLIST: &str = "blocklist";
+pub const COST_BASIS: &str = "cost_basis";
+pub const BOOTSTRAP_LIQUIDITY: &str = "bootstrap_liquidity";
 pub const METADATA: &str = "metadata";
+pub const EVENT_AUTHORITY: &str = "__event_authority"; // seed of the event authority `event_cpi` adds to the accounts
+pub const METADATA_MAX_LEN: usize = 679; // max size of a token metadata account
 pub const LAMPORT_DECIMALS: u8 = 9;
+pub const SPOT_PRICE_SCALE: u32 = 9; // spot prices are lamports per base token unit * 10^9
 pub const TOKEN_LAUNCH: &str = "token_launch";
 pub const CONSTANT: f64 = 23_625_000_000.0;
 pub const VIRTUAL_SOL: f64 = 20.0;
@@ -17,5 +28,20 @@ pub const INITIAL_RAYDIUM_SOL_AMOUNT: u64 = 79_000_000_000;
 pub const METEORA_PROGRAM_KEY: &str = "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB";
 pub const METEORA_VAULT_PROGRAM_KEY: &str = "24Uqj9JCLxUeoC3hGfh5W3s9FM9uCHDS2SG3LYwBpyTi";
 pub const QUOTE_MINT: &str = "So11111111111111111111111111111111111111112";
+pub const OPENBOOK_PROGRAM_ID: Pubkey = pubkey!("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX"); // market program raydium pools trade on
+
+//  per-curve graduation dex, stored as `BondingCurve::migration_target`
+pub const MIGRATION_TARGET_RAYDIUM: u8 = 0;
+pub const MIGRATION_TARGET_METEORA: u8 = 1;
+pub const MIGRATION_TARGET_ORCA: u8 = 2;
+//  targets `migrate` has a cpi path for, launches reject the rest
+pub const SUPPORTED_MIGRATION_TARGETS: &[u8] = &[MIGRATION_TARGET_RAYDIUM];
+
+//  `Config::fee_mode`, how trades pay the team fee
+pub const FEE_MODE_TRANSFER: u8 = 0; //  sent to the team wallet on every trade
+pub const FEE_MODE_ACCRUE: u8 = 1; //  kept in the sol vault until `claim_team_fees`
+
+//  `swap` direction for exact-output buys, 0 buys and 1 sells with an exact input
+pub const SWAP_BUY_EXACT_OUT: u8 = 2; //  `amount` is the tokens to receive, `max_input` caps the lamports in
 
 pub const TOKEN_VAULT_SEED: &str = "token_vault";
@@ -96,4 +96,124 @@ pub enum ContractError {
 
     #[msg("Slippage Exceeded")]
     SlippageExceeded,
+
+    #[msg("Not enough SOL to pay the launch rent")]
+    InsufficientLaunchFunds,
+
+    #[msg("Invalid token account")]
+    InvalidTokenAccount,
+
+    #[msg("Too many launches from this creator, try again later")]
+    LaunchRateLimited,
+
+    #[msg("Buy exceeds the maximum share of the curve reserves")]
+    BuyTooLarge,
+
+    #[msg("Curve has reached its SOL cap")]
+    CurveFull,
+
+    #[msg("This wallet is blocked from trading")]
+    WalletBlocked,
+
+    #[msg("Bonding curve is not migrated")]
+    NotMigrated,
+
+    #[msg("Global config is not initialized")]
+    ConfigNotInitialized,
+
+    #[msg("A bonding curve already exists for this mint")]
+    BondingCurveExists,
+
+    #[msg("Trade needs a non-zero minimum output")]
+    SlippageProtectionRequired,
+
+    #[msg("Buys are disabled")]
+    BuysDisabled,
+
+    #[msg("Sells are disabled")]
+    SellsDisabled,
+
+    #[msg("Mint authority was not revoked")]
+    MintAuthorityNotRevoked,
+
+    #[msg("Mint supply does not match the launch supply")]
+    SupplyMismatch,
+
+    #[msg("Bonding curve has already been traded")]
+    CurveAlreadyTraded,
+
+    #[msg("Curve reserves do not match the vault balance")]
+    ReserveMismatch,
+
+    #[msg("Curve SOL is still in the shared vault, run migrate_curve_vault first")]
+    CurveVaultNotMigrated,
+
+    #[msg("Collection accounts are missing or don't match the collection mint")]
+    InvalidCollection,
+
+    #[msg("Creator has to keep part of the dev-buy until the curve graduates")]
+    CreatorSellLimited,
+
+    #[msg("Mint account is not a fresh, uninitialized mint")]
+    InvalidMint,
+
+    #[msg("Metadata uri does not start with an allowed prefix")]
+    InvalidMetadataUri,
+
+    #[msg("Global vault does not match its seeds and bump")]
+    InvalidGlobalVault,
+
+    #[msg("Global LP account holds less than the locked minimum liquidity")]
+    InsufficientLpToLock,
+
+    #[msg("Too many graduated curves are waiting for migration")]
+    MigrationBacklogFull,
+
+    #[msg("Cost basis account is required while the loss sell grace is enabled")]
+    CostBasisRequired,
+
+    #[msg("Sell is too large this close to graduation")]
+    SellLimitedNearGraduation,
+
+    #[msg("Curve reached its maximum number of holders")]
+    MaxHoldersReached,
+
+    #[msg("Raydium pool already exists for this mint")]
+    PoolAlreadyExists,
+
+    #[msg("Migration amounts are too low to open a Raydium pool")]
+    MigrationAmountTooLow,
+
+    #[msg("Raydium pool creation failed")]
+    RaydiumCpiFailed,
+
+    #[msg("Buy is below the minimum amount")]
+    BuyTooSmall,
+
+    #[msg("Migration target is not supported")]
+    UnsupportedMigrationTarget,
+
+    #[msg("Curve graduated too recently to migrate")]
+    MigrationTooEarly,
+
+    #[msg("No team fees accrued on this curve")]
+    NoTeamFeesAccrued,
+
+    #[msg("Fee exceeds the protocol fee ceiling")]
+    FeeExceedsCeiling,
+
+    #[msg("Creator has to be verified in the token metadata")]
+    CreatorVerificationRequired,
+
+    #[msg("Transaction landed after its deadline")]
+    TransactionExpired,
+
+    #[msg("Invalid WSOL account")]
+    InvalidWsolAccount,
+
+    #[msg("Unknown swap direction")]
+    InvalidDirection,
+
+    #[msg("Config still has curves trading or waiting for migration")]
+    ConfigInUse,
 }
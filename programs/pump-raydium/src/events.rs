@@ -1,3 +1,12 @@
+use crate::{
+    constants::SPOT_PRICE_SCALE,
+    state::{
+        bondingcurve::{BondingCurve, MigrationAmounts},
+        config::MigrationSurplus,
+        launch_params::TokenLaunchParams,
+    },
+    utils::FeeBreakdown,
+};
 use anchor_lang::prelude::*;
 
 #[event]
@@ -9,9 +18,38 @@ pub struct LaunchEvent {
 
     pub decimals: u8,
     pub token_supply: u64,
+    pub token_total_supply_ui: u64, //  `token_supply` in whole tokens, `token_supply / 10^decimals`
 
     pub reserve_lamport: u64,
     pub reserve_token: u64,
+
+    pub seller_fee_basis_points: u16, //  metadata royalty, 0 unless the creator set one
+    pub is_mutable: bool,             //  whether the metadata can still be updated
+}
+
+impl LaunchEvent {
+    pub fn new(
+        creator: Pubkey,
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        metadata: Pubkey,
+        params: &TokenLaunchParams,
+        reserve_token: u64,
+    ) -> Self {
+        Self {
+            creator,
+            mint,
+            bonding_curve,
+            metadata,
+            decimals: params.decimals,
+            token_supply: params.token_supply,
+            token_total_supply_ui: params.token_supply / params.decimal_multiplier(),
+            reserve_lamport: params.reserve_lamport,
+            reserve_token,
+            seller_fee_basis_points: params.seller_fee_basis_points.unwrap_or(0),
+            is_mutable: params.is_mutable(),
+        }
+    }
 }
 
 #[event]
@@ -27,6 +65,69 @@ pub struct SwapEvent {
 
     pub virtual_sol_reserves: u64,
     pub virtual_token_reserves: u64,
+
+    pub snipe_tax_applied: bool,
+
+    pub spot_price_scaled: u64, //  lamports per base token unit after the trade, scaled by 10^SPOT_PRICE_SCALE
+
+    pub new_ath_price_scaled: Option<u64>, //  set when the trade broke the curve's all-time high
+}
+
+impl SwapEvent {
+    //  reads the curve after the trade, `ath_price_before` is its all-time high before the trade
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user: Pubkey,
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        curve: &BondingCurve,
+        amount_in: u64,
+        direction: u8,
+        minimum_receive_amount: u64,
+        amount_out: u64,
+        slot: u64,
+        ath_price_before: u64,
+    ) -> Self {
+        Self {
+            user,
+            mint,
+            bonding_curve,
+
+            amount_in,
+            direction,
+            minimum_receive_amount,
+            amount_out,
+
+            virtual_sol_reserves: curve.virtual_sol_reserves,
+            virtual_token_reserves: curve.virtual_token_reserves,
+
+            snipe_tax_applied: direction != 1 && curve.snipe_tax_active(slot),
+
+            spot_price_scaled: curve.spot_price_lamports_per_token_scaled(SPOT_PRICE_SCALE),
+
+            new_ath_price_scaled: (curve.ath_price_scaled > ath_price_before)
+                .then_some(curve.ath_price_scaled),
+        }
+    }
+}
+
+#[event]
+pub struct TradeClampedEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub direction: u8,
+    pub requested_amount: u64,
+    pub executed_amount: u64, //  largest size that kept the requested slippage rate
+    pub amount_out: u64,
+}
+
+#[event]
+pub struct ReserveSnapshot {
+    pub mint: Pubkey,
+    pub virtual_sol: u64,
+    pub virtual_token: u64,
+    pub timestamp: i64,
 }
 
 #[event]
@@ -34,6 +135,9 @@ pub struct CompleteEvent {
     pub user: Pubkey,
     pub mint: Pubkey,
     pub bonding_curve: Pubkey,
+    pub forced: bool, //  completed by the admin `force_complete` escape hatch
+    pub migration_sol_amount: u64,
+    pub migration_token_amount: u64,
 }
 
 #[event]
@@ -44,3 +148,237 @@ pub struct MigrateEvent {
     pub sol_in: u64,
     pub lp_mint: Pubkey,
 }
+
+#[event]
+pub struct ReservesAdjustedEvent {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub old_virtual_sol_reserves: u64,
+    pub old_virtual_token_reserves: u64,
+    pub new_virtual_sol_reserves: u64,
+    pub new_virtual_token_reserves: u64,
+}
+
+#[event]
+pub struct CreatorTransferEvent {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub old_creator: Pubkey,
+    pub new_creator: Pubkey,
+}
+
+#[event]
+pub struct MigrationPreviewEvent {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub fee_amount: u64,
+    pub burn_amount: u64,
+    pub surplus_amount: u64,
+    pub lp_locked: u64,
+    pub initial_price_scaled: u64, //  lamports per whole token * 1e9
+}
+
+#[event]
+pub struct BurnEvent {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub amount: u64, //  unsold tokens burned at migration
+}
+
+#[event]
+pub struct LiquiditySeededEvent {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64, //  bootstrap tokens released for the early dex pool
+}
+
+#[event]
+pub struct SolWrappedEvent {
+    pub amount: u64, //  lamports moved from the global vault into its WSOL account
+}
+
+#[event]
+pub struct SolUnwrappedEvent {
+    pub amount: u64, //  WSOL balance returned to the global vault, excluding the account rent
+}
+
+#[event]
+pub struct DustSweptEvent {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CurveVaultMigratedEvent {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub amount: u64, //  real sol reserves moved out of the shared global vault
+}
+
+#[event]
+pub struct GraduationQuoteEvent {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub buy_amount: u64,   //  lamports of the completing buy, fees included
+    pub net_lamports: u64, //  lamports that reach the curve
+    pub real_sol_reserves: u64,
+    pub graduation_target: u64,
+}
+
+#[event]
+pub struct GraduationBuyEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub buy_amount: u64,      //  lamports charged, fees included
+    pub net_lamports: u64,    //  lamports that reached the curve
+    pub token_amount: u64,    //  the last tokens of the curve
+    pub unused_lamports: u64, //  part of `max_lamports` the buy didn't need
+}
+
+#[event]
+pub struct ConfigCreatedEvent {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub data_len: u64,
+    pub lamports: u64, //  rent paid by the authority
+}
+
+#[event]
+pub struct ConfigReallocatedEvent {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub previous_len: u64,
+    pub data_len: u64,
+    pub rent_top_up: u64, //  lamports the authority added to keep the grown config rent exempt
+}
+
+#[event]
+pub struct GlobalVaultSeededEvent {
+    pub global_vault: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct SellReflectionEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub amount: u64, //  sell proceeds credited back to the curve reserves
+}
+
+#[event]
+pub struct MigrationEvent {
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub lp_tokens: u64, //  LP tokens left with the global vault after the lock
+    pub sol_deposited: u64,
+    pub token_deposited: u64,
+    pub lp_locked: u64, //  LP tokens burned for good, see `Config::locked_lp_amount`
+    pub timestamp: i64,
+}
+
+impl MigrationEvent {
+    pub fn new(
+        mint: Pubkey,
+        pool: Pubkey,
+        lp_tokens: u64,
+        amounts: &MigrationAmounts,
+        lp_locked: u64,
+        timestamp: i64,
+    ) -> Self {
+        Self {
+            mint,
+            pool,
+            lp_tokens,
+            sol_deposited: amounts.sol_amount,
+            token_deposited: amounts.token_amount,
+            lp_locked,
+            timestamp,
+        }
+    }
+}
+
+#[event]
+pub struct LossSellGraceEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub fee_bps: u16, //  discounted fee the sell paid for exiting below the average entry
+}
+
+#[event]
+pub struct PositionEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub tokens_held: u64,
+    pub cost_basis: u64,    //  lamports paid for the held tokens that were bought through the curve
+    pub current_value: u64, //  lamports a sell of `tokens_held` would pay now, fees included
+    pub unrealized_pnl: i64,
+    pub realized_pnl: i64,
+}
+
+#[event]
+pub struct MigrationSurplusEvent {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub mode: MigrationSurplus,
+    pub sol_returned: u64,  //  sent to the team wallet
+    pub tokens_burned: u64, //  burned on top of the unsold tokens
+}
+
+//  emitted next to every `SwapEvent` and by the other trades, `gross_amount` = `fee_amount` + `net_amount`
+//  buys: `gross_amount` is what the buyer paid, the fee includes the anti-snipe tax
+//  sells: `net_amount` is what the seller received, the reflection is in `SellReflectionEvent`
+#[event]
+pub struct TradeBreakdownEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub direction: u8,
+    pub gross_amount: u64,
+    pub fee_amount: u64,
+    pub net_amount: u64,
+    pub fee_bps: u16,
+}
+
+impl TradeBreakdownEvent {
+    pub fn new(
+        user: Pubkey,
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        direction: u8,
+        fees: &FeeBreakdown,
+    ) -> Self {
+        Self {
+            user,
+            mint,
+            bonding_curve,
+            direction,
+            gross_amount: fees.gross_amount,
+            fee_amount: fees.fee_amount,
+            net_amount: fees.net_amount,
+            fee_bps: fees.fee_bps,
+        }
+    }
+}
+
+#[event]
+pub struct TeamFeesAccruedEvent {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub amount: u64,
+    pub total_accrued: u64, //  unclaimed fees on the curve after this trade
+}
+
+#[event]
+pub struct TeamFeesClaimedEvent {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub team_wallet: Pubkey,
+    pub amount: u64,
+}
@@ -0,0 +1,90 @@
+use crate::errors::*;
+use crate::{
+    constants::{BONDING_CURVE, CONFIG},
+    emit_event,
+    events::ReservesAdjustedEvent,
+    state::{bondingcurve::*, config::*},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+/// Escape hatch for a launch with mistyped reserves. Only the config authority can use it and
+/// only before the first trade, once anyone has traded against the curve the reserves are final.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AdminSetReserves<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        has_one = authority @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+}
+
+impl<'info> AdminSetReserves<'info> {
+    pub fn handler(
+        &mut self,
+        virtual_sol_reserves: u64,
+        virtual_token_reserves: u64,
+    ) -> Result<()> {
+        let bonding_curve = &mut self.bonding_curve;
+
+        require!(
+            !bonding_curve.is_completed,
+            ContractError::CurveAlreadyCompleted
+        );
+        require!(bonding_curve.trade_count == 0, ContractError::CurveAlreadyTraded);
+
+        if virtual_sol_reserves == 0 || virtual_token_reserves == 0 {
+            msg!("virtual reserves can't be zero");
+            return err!(ContractError::ValueInvalid);
+        }
+        self.global_config
+            .lamport_amount_config
+            .validate("virtual_sol_reserves", &virtual_sol_reserves)?;
+        if virtual_token_reserves.checked_mul(1_000).is_none() {
+            msg!("virtual_token_reserves: {virtual_token_reserves} overflows the curve math");
+            return err!(ContractError::ValueInvalid);
+        }
+        bonding_curve.validate_reserves(virtual_sol_reserves, virtual_token_reserves)?;
+
+        let old_virtual_sol_reserves = bonding_curve.virtual_sol_reserves;
+        let old_virtual_token_reserves = bonding_curve.virtual_token_reserves;
+
+        bonding_curve.init_lamport = virtual_sol_reserves;
+        bonding_curve.virtual_sol_reserves = virtual_sol_reserves;
+        bonding_curve.virtual_token_reserves = virtual_token_reserves;
+
+        msg!(
+            "WARNING: virtual reserves of {} changed from {}/{} to {}/{}",
+            bonding_curve.key(),
+            old_virtual_sol_reserves,
+            old_virtual_token_reserves,
+            virtual_sol_reserves,
+            virtual_token_reserves
+        );
+
+        emit_event!(self, ReservesAdjustedEvent {
+            authority: self.authority.key(),
+            mint: self.token_mint.key(),
+            bonding_curve: self.bonding_curve.key(),
+            old_virtual_sol_reserves,
+            old_virtual_token_reserves,
+            new_virtual_sol_reserves: virtual_sol_reserves,
+            new_virtual_token_reserves: virtual_token_reserves,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use crate::errors::*;
+use crate::{
+    constants::{BLOCKLIST, CONFIG},
+    state::{blocklist::*, config::*},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct BlockWallet<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        has_one = authority @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<BlockedWallet>(),
+        seeds = [BLOCKLIST.as_bytes(), wallet.as_ref()],
+        bump
+    )]
+    blocked_wallet: Box<Account<'info, BlockedWallet>>,
+
+    system_program: Program<'info, System>,
+}
+
+impl<'info> BlockWallet<'info> {
+    pub fn handler(&mut self, wallet: Pubkey) -> Result<()> {
+        self.blocked_wallet.wallet = wallet;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct UnblockWallet<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        has_one = authority @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [BLOCKLIST.as_bytes(), wallet.as_ref()],
+        bump,
+        close = authority
+    )]
+    blocked_wallet: Box<Account<'info, BlockedWallet>>,
+}
+
+impl<'info> UnblockWallet<'info> {
+    pub fn handler(&mut self, _wallet: Pubkey) -> Result<()> {
+        Ok(())
+    }
+}
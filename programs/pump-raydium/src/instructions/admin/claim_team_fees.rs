@@ -0,0 +1,98 @@
+use crate::{
+    constants::{BONDING_CURVE, CONFIG, CURVE_VAULT, GLOBAL},
+    emit_event,
+    errors::*,
+    events::TeamFeesClaimedEvent,
+    state::{bondingcurve::*, config::*},
+    utils::{sol_transfer_with_signer, validate_global_vault},
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+
+//  pays the fees a curve accrued under `FEE_MODE_ACCRUE` out to the team wallet
+//  permissionless, the fees can only go to the configured team wallet
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClaimTeamFees<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: should be same with the address in the global_config
+    #[account(
+        mut,
+        constraint = global_config.team_wallet == team_wallet.key() @ContractError::IncorrectAuthority
+    )]
+    team_wallet: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    /// CHECK: global vault pda, holds the fees of shared-vault curves
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    global_vault: AccountInfo<'info>,
+
+    /// CHECK: per-curve vault pda, holds the fees of curves on their own vault
+    #[account(
+        mut,
+        seeds = [CURVE_VAULT.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    curve_vault: AccountInfo<'info>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimTeamFees<'info> {
+    pub fn handler(&mut self, global_vault_bump: u8, curve_vault_bump: u8) -> Result<u64> {
+        validate_global_vault(&self.global_vault, global_vault_bump)?;
+
+        let amount = self.bonding_curve.team_fees_accrued;
+        require!(amount > 0, ContractError::NoTeamFeesAccrued);
+
+        let token_mint_key = self.token_mint.key();
+        let global_vault_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+        let curve_vault_seeds: &[&[&[u8]]] = &[&[
+            CURVE_VAULT.as_bytes(),
+            token_mint_key.as_ref(),
+            &[curve_vault_bump],
+        ]];
+        //  fees sit in whichever vault holds the curve's sol
+        let (source, signer_seeds) = if self.bonding_curve.uses_curve_vault {
+            (&self.curve_vault, curve_vault_seeds)
+        } else {
+            (&self.global_vault, global_vault_seeds)
+        };
+
+        sol_transfer_with_signer(
+            source.clone(),
+            self.team_wallet.clone(),
+            &self.system_program,
+            signer_seeds,
+            amount,
+        )?;
+        self.bonding_curve.team_fees_accrued = 0;
+
+        emit_event!(self, TeamFeesClaimedEvent {
+            mint: self.token_mint.key(),
+            bonding_curve: self.bonding_curve.key(),
+            team_wallet: self.team_wallet.key(),
+            amount,
+        });
+
+        Ok(amount)
+    }
+}
@@ -0,0 +1,27 @@
+use crate::errors::*;
+use crate::{constants::CONFIG, state::config::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseConfig<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        has_one = authority @ContractError::IncorrectAuthority,
+        close = authority
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> CloseConfig<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        //  existing bonding curves can't be traded or migrated without the config
+        self.global_config.check_closable()?;
+
+        Ok(())
+    }
+}
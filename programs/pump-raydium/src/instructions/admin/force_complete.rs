@@ -0,0 +1,80 @@
+use crate::errors::*;
+use crate::{
+    constants::{BONDING_CURVE, CONFIG, GLOBAL},
+    emit_event,
+    events::CompleteEvent,
+    state::{bondingcurve::*, config::*},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+/// Escape hatch for a curve stranded below its graduation target with no buyers left.
+/// Only the config authority can force it, trading stops and the curve can be migrated
+/// with whatever reserves it holds.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ForceComplete<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        has_one = authority @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    global_vault: UncheckedAccount<'info>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = token_mint,
+        associated_token::authority = global_vault
+    )]
+    global_token_account: Box<Account<'info, TokenAccount>>,
+}
+
+impl<'info> ForceComplete<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let bonding_curve = &mut self.bonding_curve;
+
+        require!(
+            !bonding_curve.is_completed,
+            ContractError::CurveAlreadyCompleted
+        );
+
+        //  lower the target to the stranded reserves so `migrate` accepts the curve
+        bonding_curve.graduation_target = bonding_curve.real_sol_reserves;
+        bonding_curve.ready_to_complete = true;
+        bonding_curve.is_completed = true;
+        bonding_curve.mark_completed_at(Clock::get()?.unix_timestamp);
+        //  not held back by `max_pending_migrations`, the authority decides when to force
+        self.global_config.record_completion();
+        bonding_curve
+            .lock_migration_amounts(&self.global_config, self.global_token_account.amount)?;
+
+        emit_event!(self, CompleteEvent {
+            user: self.authority.key(),
+            mint: self.token_mint.key(),
+            bonding_curve: bonding_curve.key(),
+            forced: true,
+            migration_sol_amount: bonding_curve.migration_sol_amount,
+            migration_token_amount: bonding_curve.migration_token_amount
+        });
+
+        Ok(())
+    }
+}
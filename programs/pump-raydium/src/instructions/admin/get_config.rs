@@ -0,0 +1,21 @@
+use crate::{constants::CONFIG, state::config::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct GetConfig<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> GetConfig<'info> {
+    //  view only, clients simulate it and read the config from the return data
+    pub fn handler(&self) -> Result<Config> {
+        let config = (**self.global_config).clone();
+        msg!("{:?}", config);
+
+        Ok(config)
+    }
+}
@@ -0,0 +1,91 @@
+use crate::errors::*;
+use crate::{
+    constants::{CONFIG, GLOBAL},
+    emit_event,
+    events::{ConfigCreatedEvent, GlobalVaultSeededEvent},
+    state::config::*,
+    utils::sol_transfer_from_user,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(new_config: Config)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    payer: Signer<'info>, // creator, becomes the config authority
+
+    #[account(
+        init,
+        payer = payer,
+        space = new_config.space(),
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    config: Box<Account<'info, Config>>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    pub global_vault: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = native_mint,
+        associated_token::authority = global_vault
+    )]
+    global_wsol_account: Box<Account<'info, TokenAccount>>, // holds WSOL conrolled by global_vault
+
+    #[account(
+        address = spl_token::native_mint::ID
+    )]
+    native_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+
+    token_program: Program<'info, Token>,
+
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> InitializeConfig<'info> {
+    pub fn handler(&mut self, mut new_config: Config) -> Result<()> {
+        new_config.prepare(None)?;
+        require_keys_eq!(new_config.authority, self.payer.key(), ContractError::IncorrectAuthority);
+
+        let config_info = self.config.to_account_info();
+        emit_event!(self, ConfigCreatedEvent {
+            config: config_info.key(),
+            authority: self.payer.key(),
+            data_len: config_info.data_len() as u64,
+            lamports: config_info.lamports(),
+        });
+        self.config.set_inner(new_config);
+
+        //  initialize global vault if it hasn't been
+        if self.global_vault.lamports() == 0 {
+            let lamports = 1000000;
+            sol_transfer_from_user(
+                &self.payer,
+                self.global_vault.clone(),
+                &self.system_program,
+                lamports,
+            )?;
+
+            emit_event!(self, GlobalVaultSeededEvent {
+                global_vault: self.global_vault.key(),
+                lamports,
+            });
+        }
+        Ok(())
+    }
+}
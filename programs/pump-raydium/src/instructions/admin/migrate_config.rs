@@ -0,0 +1,74 @@
+use crate::errors::*;
+use crate::{constants::CONFIG, state::config::*};
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    /// CHECK: legacy config, decoded by `stored_authority` and rewritten in the current layout
+    #[account(
+        mut,
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    config: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateConfig<'info> {
+    //  one time bridge for configs stored before the `version` field. they don't decode as a
+    //  `Config`, so `configure` can't take them until they're rewritten here
+    pub fn handler(&mut self) -> Result<()> {
+        let (version, authority) = Config::stored_authority(&self.config.try_borrow_data()?)?;
+        require_keys_eq!(authority, self.authority.key(), ContractError::IncorrectAuthority);
+        if version != LEGACY_CONFIG_VERSION {
+            msg!("config is already at version {version}");
+            return err!(ContractError::IncorrectConfigAccount);
+        }
+
+        let legacy_config = LegacyConfig::deserialize(&mut &self.config.try_borrow_data()?[8..])?;
+        let global_config = legacy_config.upgrade();
+        let mut serialized_config = Vec::new();
+        global_config.try_serialize(&mut serialized_config)?;
+        let serialized_config_len = serialized_config.len();
+
+        //  size the account to exactly fit, refunding the rent of any stale trailing bytes
+        let config_cost = Rent::get()?.minimum_balance(serialized_config_len);
+        let config_lamports = self.config.lamports();
+        if config_lamports < config_cost {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: self.authority.to_account_info(),
+                        to: self.config.to_account_info(),
+                    },
+                ),
+                config_cost - config_lamports,
+            )?;
+        } else if config_lamports > config_cost {
+            let refund = config_lamports - config_cost;
+            **self.config.try_borrow_mut_lamports()? -= refund;
+            **self.authority.try_borrow_mut_lamports()? += refund;
+        }
+        self.config.realloc(serialized_config_len, false)?;
+
+        self.config
+            .try_borrow_mut_data()?
+            .copy_from_slice(serialized_config.as_slice());
+
+        //  read it back the way every other instruction does and make sure nothing changed
+        let migrated_config = Config::try_deserialize(&mut &self.config.try_borrow_data()?[..])?;
+        require!(
+            migrated_config == global_config,
+            ContractError::IncorrectConfigAccount
+        );
+        msg!("config migrated to version {CONFIG_VERSION}, {serialized_config_len} bytes");
+
+        Ok(())
+    }
+}
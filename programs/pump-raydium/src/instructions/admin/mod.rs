@@ -1 +1,11 @@
-pub mod configure;
\ No newline at end of file
+pub mod configure;
+pub mod initialize_config;
+pub mod close_config;
+pub mod blocklist;pub mod get_config;
+pub mod force_complete;
+pub mod vault_wsol;
+pub mod top_up_vault;
+pub mod set_trading;
+pub mod migrate_config;
+pub mod admin_set_reserves;
+pub mod claim_team_fees;
@@ -0,0 +1,27 @@
+use crate::errors::*;
+use crate::{constants::CONFIG, state::config::*};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetTrading<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        has_one = authority @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> SetTrading<'info> {
+    //  incident response, e.g. stop sells during a bank run while buys keep working
+    pub fn handler(&mut self, buys_enabled: bool, sells_enabled: bool) -> Result<()> {
+        self.global_config.buys_enabled = buys_enabled;
+        self.global_config.sells_enabled = sells_enabled;
+        msg!("buys enabled: {buys_enabled}, sells enabled: {sells_enabled}");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{constants::GLOBAL, utils::sol_transfer_from_user};
+use anchor_lang::{prelude::*, system_program};
+
+//  permissionless, it can only add lamports to the vault
+#[derive(Accounts)]
+pub struct TopUpVault<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    global_vault: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> TopUpVault<'info> {
+    //  restores rent exemption for the vault's current size, `configure` only seeds a fixed amount
+    pub fn handler(&mut self) -> Result<()> {
+        let min_balance = Rent::get()?.minimum_balance(self.global_vault.data_len());
+        let shortfall = min_balance.saturating_sub(self.global_vault.lamports());
+
+        if shortfall > 0 {
+            sol_transfer_from_user(
+                &self.payer,
+                self.global_vault.clone(),
+                &self.system_program,
+                shortfall,
+            )?;
+        }
+        msg!("topped up the global vault by {shortfall} lamports");
+
+        Ok(())
+    }
+}
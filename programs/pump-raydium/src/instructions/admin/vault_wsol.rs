@@ -0,0 +1,129 @@
+use crate::errors::*;
+use crate::{
+    constants::{CONFIG, GLOBAL},
+    emit_event,
+    events::{SolUnwrappedEvent, SolWrappedEvent},
+    state::config::*,
+    utils::{unwrap_sol_with_signer, validate_global_vault, wrap_sol_with_signer},
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct WrapSol<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        has_one = authority @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    global_vault: AccountInfo<'info>,
+
+    //  recreated here if a previous `unwrap_sol` closed it
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = native_mint,
+        associated_token::authority = global_vault
+    )]
+    global_wsol_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = spl_token::native_mint::ID)]
+    native_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+
+    token_program: Program<'info, Token>,
+
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> WrapSol<'info> {
+    //  moves `amount` lamports from the vault into its WSOL account, the vault stays rent exempt
+    pub fn handler(&mut self, amount: u64, global_vault_bump: u8) -> Result<()> {
+        require!(amount > 0, ContractError::InvalidAmount);
+        validate_global_vault(&self.global_vault, global_vault_bump)?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+        wrap_sol_with_signer(
+            self.global_vault.to_account_info(),
+            self.global_wsol_account.to_account_info(),
+            &self.system_program,
+            &self.token_program,
+            signer_seeds,
+            amount,
+        )?;
+
+        emit_event!(self, SolWrappedEvent { amount });
+
+        Ok(())
+    }
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UnwrapSol<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        has_one = authority @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    global_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = native_mint,
+        associated_token::authority = global_vault
+    )]
+    global_wsol_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = spl_token::native_mint::ID)]
+    native_mint: Box<Account<'info, Mint>>,
+
+    token_program: Program<'info, Token>,
+}
+
+impl<'info> UnwrapSol<'info> {
+    //  unwraps the whole WSOL balance, the account is closed and its rent returns to the vault
+    pub fn handler(&mut self, global_vault_bump: u8) -> Result<()> {
+        validate_global_vault(&self.global_vault, global_vault_bump)?;
+        let amount = self.global_wsol_account.amount;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+        unwrap_sol_with_signer(
+            self.global_wsol_account.to_account_info(),
+            self.global_vault.to_account_info(),
+            &self.token_program,
+            signer_seeds,
+        )?;
+
+        emit_event!(self, SolUnwrappedEvent { amount });
+
+        Ok(())
+    }
+}
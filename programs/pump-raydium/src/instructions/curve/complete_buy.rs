@@ -0,0 +1,246 @@
+use crate::{
+    constants::{BLOCKLIST, BONDING_CURVE, CONFIG, COST_BASIS, CURVE_VAULT, GLOBAL},
+    emit_event,
+    errors::*,
+    events::{CompleteEvent, GraduationBuyEvent, TeamFeesAccruedEvent, TradeBreakdownEvent},
+    state::{bondingcurve::*, config::*, cost_basis::*},
+    utils::validate_global_vault,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    associated_token,
+    token::{self, accessor, Mint, Token},
+};
+
+//  the buy that finishes the curve, sized on-chain so keepers don't have to predict the
+//  partial fill. it completes the curve in the same transaction, no `finalize` needed
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CompleteBuy<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: should be same with the address in the global_config
+    #[account(
+        mut,
+        constraint = global_config.team_wallet == team_wallet.key() @ContractError::IncorrectAuthority
+    )]
+    pub team_wallet: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: Account<'info, BondingCurve>,
+
+    /// CHECK: global vault pda, owns the curve tokens
+    #[account(
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    pub global_vault: AccountInfo<'info>,
+
+    /// CHECK: per-curve vault pda which stores this curve's SOL
+    #[account(
+        mut,
+        seeds = [CURVE_VAULT.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub curve_vault: AccountInfo<'info>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: ata of global vault
+    #[account(
+        mut,
+        seeds = [
+            global_vault.key().as_ref(),
+            anchor_spl::token::spl_token::ID.as_ref(),
+            token_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = anchor_spl::associated_token::ID
+    )]
+    global_ata: AccountInfo<'info>,
+
+    /// CHECK: canonical ata of (user, token_mint), any other token account is rejected
+    #[account(
+        mut,
+        address = associated_token::get_associated_token_address(&user.key(), &token_mint.key())
+            @ContractError::InvalidTokenAccount,
+        constraint = user_ata.owner == &token::ID @ContractError::InvalidTokenAccount
+    )]
+    user_ata: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: blocklist pda of the user, required when the config enforces the blocklist
+    #[account(
+        seeds = [BLOCKLIST.as_bytes(), user.key().as_ref()],
+        bump
+    )]
+    blocked_wallet: Option<UncheckedAccount<'info>>,
+
+    /// cost basis of the user for this curve, required while `Config::tracks_positions`
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<CostBasis>(),
+        seeds = [COST_BASIS.as_bytes(), token_mint.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    cost_basis: Option<Box<Account<'info, CostBasis>>>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    #[account(address = token::ID)]
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> CompleteBuy<'info> {
+    //  `max_lamports` caps what the caller is willing to spend, fees included
+    //  only the quoted amount is transferred, the rest of the budget never leaves the buyer
+    pub fn handler(
+        &mut self,
+        max_lamports: u64,
+        global_vault_bump: u8,
+        curve_vault_bump: u8,
+    ) -> Result<u64> {
+        validate_global_vault(&self.global_vault, global_vault_bump)?;
+        let bonding_curve = &mut self.bonding_curve;
+
+        require!(
+            !bonding_curve.is_completed && !bonding_curve.ready_to_complete,
+            ContractError::CurveAlreadyCompleted
+        );
+        require!(bonding_curve.uses_curve_vault, ContractError::CurveVaultNotMigrated);
+
+        if self.global_config.enforce_blocklist {
+            let blocked_wallet = self
+                .blocked_wallet
+                .as_ref()
+                .ok_or(ContractError::WalletBlocked)?;
+            require!(blocked_wallet.data_is_empty(), ContractError::WalletBlocked);
+        }
+
+        let slot = Clock::get()?.slot;
+        let quote = bonding_curve
+            .quote_to_graduate(&self.global_config, slot)
+            .ok_or(ContractError::ArithmeticError)?;
+        if quote.buy_amount > max_lamports {
+            msg!("completing buy needs {} lamports, max is {max_lamports}", quote.buy_amount);
+            return err!(ContractError::SlippageExceeded);
+        }
+
+        //  same transaction as the quote, so the exact output is known
+        let expected_tokens = bonding_curve
+            .quote(&self.global_config, quote.buy_amount, 0, slot)
+            .ok_or(ContractError::BuyFailed)?;
+
+        let cost_basis = self.cost_basis.as_deref().map(|cost_basis| &**cost_basis);
+        bonding_curve.open_position(&self.global_config, cost_basis, 0)?;
+        let user_lamports_before = self.user.lamports();
+
+        let token_mint_key = self.token_mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[
+            &[GLOBAL.as_bytes(), &[global_vault_bump]],
+            &[CURVE_VAULT.as_bytes(), token_mint_key.as_ref(), &[curve_vault_bump]],
+        ];
+
+        let TradeResult {
+            amount_out: token_amount,
+            fees,
+        } = bonding_curve.swap(
+            &self.global_config,
+            &mut self.global_ata,
+            &mut self.user_ata,
+            &self.global_vault,
+            &mut self.curve_vault.to_account_info(),
+            &mut self.team_wallet,
+            quote.buy_amount,
+            0,
+            expected_tokens,
+            None,
+            0,
+            &self.user,
+            signer_seeds,
+            &self.token_program,
+            &self.system_program,
+        )?;
+        require!(bonding_curve.ready_to_complete, ContractError::CurveNotCompleted);
+        if self.global_config.tracks_positions() {
+            if let Some(cost_basis) = self.cost_basis.as_mut() {
+                cost_basis.record_trade(
+                    self.user.key(),
+                    token_mint_key,
+                    0,
+                    quote.buy_amount,
+                    token_amount,
+                    user_lamports_before.saturating_sub(self.user.lamports()),
+                );
+            }
+        }
+        //  the swap prices the last buy from the same live reserves as the quote
+        if fees.net_amount != quote.net_lamports || fees.gross_amount != quote.buy_amount {
+            msg!(
+                "completing buy took {} of {} lamports, quoted {} of {}",
+                fees.net_amount,
+                fees.gross_amount,
+                quote.net_lamports,
+                quote.buy_amount
+            );
+            return err!(ContractError::BuyFailed);
+        }
+
+        //  promote to completed right away, like `finalize`, the swap checked the backlog
+        self.global_config.record_completion();
+        bonding_curve.is_completed = true;
+        bonding_curve.mark_completed_at(Clock::get()?.unix_timestamp);
+        bonding_curve.lock_migration_amounts(&self.global_config, accessor::amount(&self.global_ata)?)?;
+
+        emit_event!(self, GraduationBuyEvent {
+            user: self.user.key(),
+            mint: self.token_mint.key(),
+            bonding_curve: self.bonding_curve.key(),
+            buy_amount: fees.gross_amount,
+            net_lamports: fees.net_amount,
+            token_amount,
+            unused_lamports: max_lamports - fees.gross_amount,
+        });
+
+        emit_event!(self, TradeBreakdownEvent::new(
+            self.user.key(),
+            self.token_mint.key(),
+            self.bonding_curve.key(),
+            0,
+            &fees,
+        ));
+
+        if self.global_config.accrues_team_fees() && fees.fee_amount > 0 {
+            emit_event!(self, TeamFeesAccruedEvent {
+                mint: self.token_mint.key(),
+                bonding_curve: self.bonding_curve.key(),
+                amount: fees.fee_amount,
+                total_accrued: self.bonding_curve.team_fees_accrued,
+            });
+        }
+
+        emit_event!(self, CompleteEvent {
+            user: self.user.key(),
+            mint: self.token_mint.key(),
+            bonding_curve: self.bonding_curve.key(),
+            forced: false,
+            migration_sol_amount: self.bonding_curve.migration_sol_amount,
+            migration_token_amount: self.bonding_curve.migration_token_amount
+        });
+
+        Ok(token_amount)
+    }
+}
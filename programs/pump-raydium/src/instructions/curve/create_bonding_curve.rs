@@ -1,25 +1,40 @@
 use crate::{
-    constants::{BONDING_CURVE, CONFIG, GLOBAL, METADATA},
+    constants::{
+        BONDING_CURVE, BOOTSTRAP_LIQUIDITY, CONFIG, CREATOR_STATS, CURVE_VAULT, GLOBAL, METADATA,
+        METADATA_MAX_LEN,
+    },
+    emit_event,
     errors::*,
-    events::LaunchEvent,
-    state::{bondingcurve::*, config::*},
+    events::{LaunchEvent, TeamFeesAccruedEvent, TradeBreakdownEvent},
+    state::{
+        bondingcurve::*, bootstrap_liquidity::*, config::*, creator_stats::*, launch_params::*,
+    },
+    utils::{
+        check_launch_mint, mint_with_signer, revoke_mint_authority, sol_transfer_from_user,
+        validate_global_vault,
+    },
 };
 use anchor_lang::{prelude::*, solana_program::sysvar::SysvarId, system_program};
 use anchor_spl::{
     associated_token::{self, AssociatedToken},
-    metadata::{self, mpl_token_metadata::types::DataV2, Metadata},
-    token::{self, spl_token::instruction::AuthorityType, Mint, Token},
+    metadata::{
+        self,
+        mpl_token_metadata::types::{Collection, Creator, DataV2},
+        Metadata, MetadataAccount,
+    },
+    token::{self, Mint, Token, TokenAccount},
 };
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-#[instruction(decimals: u8)]
 pub struct CreateBondingCurve<'info> {
+    /// CHECK: loaded in the handler so a missing config fails with `ConfigNotInitialized`
     #[account(
         mut,
         seeds = [CONFIG.as_bytes()],
         bump,
     )]
-    global_config: Box<Account<'info, Config>>,
+    global_config: UncheckedAccount<'info>,
 
     /// CHECK: global vault pda which stores SOL
     #[account(
@@ -29,20 +44,25 @@ pub struct CreateBondingCurve<'info> {
     )]
     pub global_vault: AccountInfo<'info>,
 
+    /// CHECK: per-curve vault pda which stores this curve's SOL, funded with its rent below
+    #[account(
+        mut,
+        seeds = [CURVE_VAULT.as_bytes(), token.key().as_ref()],
+        bump,
+    )]
+    pub curve_vault: AccountInfo<'info>,
+
     /// User calling the instruction
     #[account(mut)]
     creator: Signer<'info>,
 
-    #[account(
-        init,
-        payer = creator,
-        mint::decimals = decimals,
-        mint::authority = global_vault.key(),
-    )]
-    token: Box<Account<'info, Mint>>,
+    /// New mint keypair, created in instruction once the curve is known to be new
+    #[account(mut)]
+    token: Signer<'info>,
 
+    //  `init_if_needed` so a relaunch reaches the handler and fails with `BondingCurveExists`
     #[account(
-        init,
+        init_if_needed,
         payer = creator,
         space = 8 + std::mem::size_of::<BondingCurve>(),
         seeds = [BONDING_CURVE.as_bytes(), &token.key().to_bytes()],
@@ -50,6 +70,15 @@ pub struct CreateBondingCurve<'info> {
     )]
     bonding_curve: Box<Account<'info, BondingCurve>>,
 
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + std::mem::size_of::<CreatorStats>(),
+        seeds = [CREATOR_STATS.as_bytes(), creator.key().as_ref()],
+        bump
+    )]
+    creator_stats: Box<Account<'info, CreatorStats>>,
+
     /// CHECK: passed to token metadata program
     #[account(
         mut,
@@ -76,6 +105,54 @@ pub struct CreateBondingCurve<'info> {
     )]
     global_token_account: UncheckedAccount<'info>, // ATA to hold new tokens
 
+    /// CHECK: created in instruction, only needed for the creator dev-buy
+    /// must be the canonical ata of (creator, token) so the dev-buy can't be routed elsewhere
+    #[account(
+        mut,
+        address = associated_token::get_associated_token_address(&creator.key(), &token.key())
+            @ContractError::InvalidTokenAccount
+    )]
+    creator_token_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: created in instruction, only needed when part of the supply goes to the team
+    #[account(
+        mut,
+        seeds = [
+            team_wallet.key().as_ref(),
+            token::spl_token::ID.as_ref(),
+            token.key().as_ref(),
+        ],
+        bump,
+        seeds::program = associated_token::ID
+    )]
+    team_token_account: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + std::mem::size_of::<BootstrapLiquidity>(),
+        seeds = [BOOTSTRAP_LIQUIDITY.as_bytes(), token.key().as_ref()],
+        bump
+    )]
+    bootstrap_liquidity: Option<Box<Account<'info, BootstrapLiquidity>>>,
+
+    /// CHECK: ata of `bootstrap_liquidity`, checked and created in instruction
+    #[account(mut)]
+    bootstrap_token_account: Option<UncheckedAccount<'info>>,
+
+    /// Collection the token is launched under, verified in the handler
+    collection_mint: Option<Box<Account<'info, Mint>>>,
+
+    /// CHECK: metadata of the collection mint, checked by the token metadata program
+    #[account(mut)]
+    collection_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: master edition of the collection mint, checked by the token metadata program
+    collection_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// Update authority of the collection, signs the verification so only its owner can link launches
+    collection_authority: Option<Signer<'info>>,
+
     #[account(address = system_program::ID)]
     system_program: Program<'info, System>,
 
@@ -91,57 +168,130 @@ pub struct CreateBondingCurve<'info> {
     #[account(address = metadata::ID)]
     mpl_token_metadata_program: Program<'info, Metadata>,
 
-    /// CHECK: should be same with the address in the global_config
-    #[account(
-        mut,
-        constraint = global_config.team_wallet == team_wallet.key() @ContractError::IncorrectAuthority
-    )]
+    /// CHECK: should be same with the address in the global_config, checked in the handler
+    #[account(mut)]
     pub team_wallet: AccountInfo<'info>,
 }
 
 impl<'info> CreateBondingCurve<'info> {
-    pub fn handler(
-        &mut self,
+    //  deployment scripts can race `configure`, report a missing config before any range validation
+    fn load_global_config(&self) -> Result<Box<Config>> {
+        if self.global_config.owner != &crate::ID || self.global_config.data_is_empty() {
+            msg!("config {} has not been created, run `configure` first", self.global_config.key());
+            return Err(ConfigNotInitialized.into());
+        }
+
+        let global_config = Config::load_checked(&self.global_config).map_err(|_| {
+            msg!("config {} could not be read, run `configure` again", self.global_config.key());
+            ConfigNotInitialized
+        })?;
+        require!(global_config.initialized, ContractError::ConfigNotInitialized);
 
-        // launch config
-        decimals: u8,
-        token_supply: u64,
-        reserve_lamport: u64,
+        Ok(Box::new(global_config))
+    }
 
-        // metadata
-        name: String,
-        symbol: String,
-        uri: String,
+    pub fn handler(
+        &mut self,
+        params: TokenLaunchParams,
         global_vault_bump: u8,
+        curve_vault_bump: u8,
     ) -> Result<()> {
-        let global_config = &self.global_config;
+        validate_global_vault(&self.global_vault, global_vault_bump)?;
+        let global_config = &mut self.load_global_config()?;
+        require_keys_eq!(
+            global_config.team_wallet,
+            self.team_wallet.key(),
+            ContractError::IncorrectAuthority
+        );
+
         let creator = &self.creator;
         let token = &self.token;
+
+        //  relaunching a mint would reuse its bonding curve pda
+        if self.bonding_curve.token_mint != Pubkey::default() {
+            msg!(
+                "bonding curve {} already exists for mint {}",
+                self.bonding_curve.key(),
+                token.key()
+            );
+            return Err(BondingCurveExists.into());
+        }
+
         let global_token_account = &self.global_token_account; // ata
         let bonding_curve = &mut self.bonding_curve; // pda
         let global_vault = &self.global_vault;
 
         //  check params
-        let decimal_multiplier = 10u64.pow(decimals as u32); // 10^6 = 1_000_000
-        let fractional_tokens = token_supply % decimal_multiplier;
-        if fractional_tokens != 0 {
-            return Err(ValueInvalid.into());
-        }
-
-        // check wether it meets min/max treshold:
-        global_config
-            .lamport_amount_config
-            .validate(&reserve_lamport)?;
+        let LaunchAllocation {
+            team_tokens,
+            bootstrap_tokens,
+            curve_tokens,
+        } = params.validate(global_config)?;
+        let graduation_target = params.graduation_target(global_config);
+        let is_mutable = params.is_mutable();
+        let migration_target = params.migration_target();
+        let launch_event = LaunchEvent::new(
+            creator.key(),
+            token.key(),
+            bonding_curve.key(),
+            self.token_metadata_account.key(),
+            &params,
+            global_config.initial_real_token_reserves_config,
+        );
+        let TokenLaunchParams {
+            decimals,
+            token_supply,
+            reserve_lamport,
+            initial_buy_lamports,
+            min_tokens_out,
+            verify_creator,
+            seller_fee_basis_points,
+            fee_override_bps,
+            name,
+            symbol,
+            uri,
+            ..
+        } = params;
+        let initial_buy_lamports = initial_buy_lamports.unwrap_or(0);
+        let seller_fee_basis_points = seller_fee_basis_points.unwrap_or(0);
+        let verify_creator = verify_creator || global_config.require_verified_creator;
 
-        global_config
-            .token_supply_config
-            .validate(&(token_supply / decimal_multiplier))?;
+        //  throttle launches per creator
+        let slot = Clock::get()?.slot;
+        let creator_stats = &mut self.creator_stats;
+        if creator_stats.launch_count > 0
+            && slot
+                < creator_stats
+                    .last_launch_slot
+                    .saturating_add(global_config.min_slots_between_launches)
+        {
+            msg!(
+                "creator launched at slot {}, next launch allowed {} slots later",
+                creator_stats.last_launch_slot,
+                global_config.min_slots_between_launches
+            );
+            return Err(LaunchRateLimited.into());
+        }
+        creator_stats.creator = creator.key();
+        creator_stats.last_launch_slot = slot;
+        creator_stats.launch_count += 1;
 
-        global_config.token_decimals_config.validate(&decimals)?;
-        //
+        //  make sure the creator can pay for the accounts created below
+        //  bonding curve rent is already paid by the account constraints
+        let rent = Rent::get()?;
+        let curve_vault_rent = rent.minimum_balance(0);
+        let launch_rent = rent.minimum_balance(Mint::LEN)
+            + rent.minimum_balance(TokenAccount::LEN)
+            + rent.minimum_balance(METADATA_MAX_LEN)
+            + curve_vault_rent;
+        if creator.lamports() < launch_rent {
+            msg!("creator needs at least {launch_rent} lamports to pay the launch rent");
+            return Err(InsufficientLaunchFunds.into());
+        }
 
         // create token launch pda:
         // pub struct BondingCurve {
+        //     pub is_completed: bool,
         //     pub token_mint: Pubkey,
         //     pub creator: Pubkey,
         //     pub init_lamport: u64,
@@ -150,17 +300,74 @@ impl<'info> CreateBondingCurve<'info> {
         //     pub virtual_token_reserves: u64,
         //     pub real_sol_reserves: u64,
         //     pub real_token_reserves: u64,
-        //     pub is_completed: bool,
+        //     pub graduation_target: u64,
+        //     pub max_sol_reserves: u64,
+        //     pub launch_slot: u64,
+        //     pub snipe_tax_slots: u64,
+        //     pub snipe_tax_bps: u16,
+        //     pub ready_to_complete: bool,
+        //     pub is_migrated: bool,
+        //     pub completed_at: i64,
+        //     pub fee_override_bps: Option<u16>,
+        //     pub migration_sol_amount: u64,
+        //     pub migration_token_amount: u64,
+        //     pub trade_count: u64,
+        //     pub uses_curve_vault: bool,
+        //     pub creator_dev_buy_tokens: u64,
+        //     pub holder_count: u64,
+        //     pub migration_target: u8,
+        //     pub team_fees_accrued: u64,
+        //     pub ath_price_scaled: u64,
         // }
         bonding_curve.token_mint = token.key();
         bonding_curve.creator = creator.key();
-        bonding_curve.init_lamport = reserve_lamport; // ???
-
-        bonding_curve.virtual_sol_reserves = global_config.initial_virtual_sol_reserves_config;
-        bonding_curve.virtual_token_reserves = global_config.initial_virtual_token_reserves_config;
-        bonding_curve.real_sol_reserves = 0;
-        bonding_curve.real_token_reserves = global_config.initial_real_token_reserves_config;
+        bonding_curve.init_reserves(
+            reserve_lamport,
+            global_config.initial_virtual_token_reserves_config,
+            global_config.initial_real_token_reserves_config,
+        );
+        //  the launch price is the first high, trades above it set a new one
+        bonding_curve.record_ath_price();
         bonding_curve.token_total_supply = token_supply; // 1B
+        bonding_curve.graduation_target = graduation_target;
+        bonding_curve.max_sol_reserves = global_config.max_sol_reserves;
+
+        bonding_curve.launch_slot = slot;
+        bonding_curve.snipe_tax_slots = global_config.snipe_tax_slots;
+        bonding_curve.snipe_tax_bps = global_config.snipe_tax_bps;
+        bonding_curve.fee_override_bps = fee_override_bps;
+        bonding_curve.uses_curve_vault = true;
+        bonding_curve.migration_target = migration_target;
+
+        //  the curve vault only ever holds this curve's sol on top of its rent
+        let curve_vault = &self.curve_vault;
+        if curve_vault.lamports() < curve_vault_rent {
+            sol_transfer_from_user(
+                creator,
+                curve_vault.to_account_info(),
+                &self.system_program,
+                curve_vault_rent - curve_vault.lamports(),
+            )?;
+        }
+
+        // create the mint, the global vault is its authority until the supply is minted
+        prepare_mint_account(
+            &token.to_account_info(),
+            &creator.to_account_info(),
+            &self.system_program.to_account_info(),
+            &rent,
+        )?;
+        token::initialize_mint2(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                token::InitializeMint2 {
+                    mint: token.to_account_info(),
+                },
+            ),
+            decimals,
+            &global_vault.key(),
+            None,
+        )?;
 
         // create global token account (for the bonding curve to hold tokens)
         associated_token::create(CpiContext::new(
@@ -179,20 +386,104 @@ impl<'info> CreateBondingCurve<'info> {
         let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
 
         // mint tokens to bonding curve & team
-        token::mint_to(
-            CpiContext::new_with_signer(
-                self.token_program.to_account_info(),
-                token::MintTo {
+        mint_with_signer(
+            token.to_account_info(),
+            global_token_account.to_account_info(),
+            global_vault.to_account_info(),
+            &self.token_program,
+            signer_seeds,
+            curve_tokens, // mints (e.g 1B tokens, less the team and bootstrap allocations)
+        )?;
+
+        if team_tokens > 0 {
+            let team_token_account = self
+                .team_token_account
+                .as_ref()
+                .ok_or(ContractError::InvalidTokenAccount)?;
+
+            associated_token::create(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: creator.to_account_info(),
+                    associated_token: team_token_account.to_account_info(),
+                    authority: self.team_wallet.to_account_info(),
                     mint: token.to_account_info(),
-                    to: global_token_account.to_account_info(),
-                    authority: global_vault.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
                 },
+            ))?;
+
+            mint_with_signer(
+                token.to_account_info(),
+                team_token_account.to_account_info(),
+                global_vault.to_account_info(),
+                &self.token_program,
                 signer_seeds,
-            ),
-            token_supply, // mints (e.g 1B tokens)
-        )?;
+                team_tokens,
+            )?;
+        }
+
+        if bootstrap_tokens > 0 {
+            let bootstrap_liquidity = self
+                .bootstrap_liquidity
+                .as_mut()
+                .ok_or(ContractError::InvalidTokenAccount)?;
+            let bootstrap_token_account = self
+                .bootstrap_token_account
+                .as_ref()
+                .ok_or(ContractError::InvalidTokenAccount)?;
+            require_keys_eq!(
+                bootstrap_token_account.key(),
+                associated_token::get_associated_token_address(
+                    &bootstrap_liquidity.key(),
+                    &token.key()
+                ),
+                ContractError::InvalidTokenAccount
+            );
+
+            associated_token::create(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: creator.to_account_info(),
+                    associated_token: bootstrap_token_account.to_account_info(),
+                    authority: bootstrap_liquidity.to_account_info(),
+                    mint: token.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                },
+            ))?;
+
+            mint_with_signer(
+                token.to_account_info(),
+                bootstrap_token_account.to_account_info(),
+                global_vault.to_account_info(),
+                &self.token_program,
+                signer_seeds,
+                bootstrap_tokens,
+            )?;
+
+            bootstrap_liquidity.mint = token.key();
+            bootstrap_liquidity.amount = bootstrap_tokens;
+        }
+
+        //  metaplex only lets the update authority be verified on creation,
+        //  the creator is added unverified and signs the metadata below
+        let creators = verify_creator.then(|| {
+            vec![Creator {
+                address: creator.key(),
+                verified: false,
+                share: 100,
+            }]
+        });
+
+        let collection = self.collection_mint.as_ref().map(|collection_mint| Collection {
+            verified: false,
+            key: collection_mint.key(),
+        });
 
         // create metadata
+        //  the creator pays this rent for good: token metadata only closes the metadata of a
+        //  fungible mint by burning the whole supply, and spl-token mints can't be closed at all
         metadata::create_metadata_accounts_v3(
             CpiContext::new_with_signer(
                 self.mpl_token_metadata_program.to_account_info(), // program to be invoked
@@ -211,43 +502,241 @@ impl<'info> CreateBondingCurve<'info> {
                 name,
                 symbol,
                 uri,
-                seller_fee_basis_points: 0,
-                creators: None,
-                collection: None,
+                seller_fee_basis_points,
+                creators,
+                collection,
                 uses: None,
             },
-            false,
+            is_mutable,
             true,
             None,
         )?;
 
-        //  revoke mint authority
-        token::set_authority(
-            CpiContext::new_with_signer(
-                self.token_program.to_account_info(),
-                token::SetAuthority {
-                    current_authority: global_vault.to_account_info(),
-                    account_or_mint: token.to_account_info(),
+        if verify_creator {
+            metadata::sign_metadata(CpiContext::new(
+                self.mpl_token_metadata_program.to_account_info(),
+                metadata::SignMetadata {
+                    creator: creator.to_account_info(),
+                    metadata: self.token_metadata_account.to_account_info(),
                 },
-                signer_seeds,
-            ),
-            AuthorityType::MintTokens,
-            None,
+            ))?;
+        }
+
+        //  read the signed metadata back, deployments requiring it get a verified creator or nothing
+        if global_config.require_verified_creator {
+            let metadata = MetadataAccount::try_deserialize(
+                &mut &self.token_metadata_account.try_borrow_data()?[..],
+            )?;
+            let verified = metadata.creators.as_ref().is_some_and(|creators| {
+                creators.iter().any(|metadata_creator| {
+                    metadata_creator.address == creator.key() && metadata_creator.verified
+                })
+            });
+            if !verified {
+                msg!("creator {} is not verified in the metadata", creator.key());
+                return Err(CreatorVerificationRequired.into());
+            }
+        }
+
+        //  the collection is added unverified like the creator, the collection authority verifies it
+        if let Some(collection_mint) = &self.collection_mint {
+            let (
+                Some(collection_metadata),
+                Some(collection_master_edition),
+                Some(collection_authority),
+            ) = (
+                &self.collection_metadata,
+                &self.collection_master_edition,
+                &self.collection_authority,
+            )
+            else {
+                msg!(
+                    "collection {} needs its metadata, master edition and authority",
+                    collection_mint.key()
+                );
+                return Err(InvalidCollection.into());
+            };
+
+            metadata::verify_sized_collection_item(
+                CpiContext::new(
+                    self.mpl_token_metadata_program.to_account_info(),
+                    metadata::VerifySizedCollectionItem {
+                        payer: creator.to_account_info(),
+                        metadata: self.token_metadata_account.to_account_info(),
+                        collection_authority: collection_authority.to_account_info(),
+                        collection_mint: collection_mint.to_account_info(),
+                        collection_metadata: collection_metadata.to_account_info(),
+                        collection_master_edition: collection_master_edition.to_account_info(),
+                    },
+                ),
+                None,
+            )?;
+        }
+
+        //  revoke mint authority
+        revoke_mint_authority(
+            token.to_account_info(),
+            global_vault.to_account_info(),
+            &self.token_program,
+            signer_seeds,
         )?;
 
+        bonding_curve.ready_to_complete = false;
         bonding_curve.is_completed = false;
 
-        emit!(LaunchEvent {
-            creator: self.creator.key(),
-            mint: self.token.key(),
-            bonding_curve: self.bonding_curve.key(),
-            metadata: self.token_metadata_account.key(),
-            decimals,
-            token_supply,
-            reserve_lamport,
-            reserve_token: global_config.initial_real_token_reserves_config
-        });
+        //  creator dev-buy
+        let mut dev_buy_fees = None;
+        if initial_buy_lamports > 0 {
+            let creator_token_account = self
+                .creator_token_account
+                .as_ref()
+                .ok_or(ContractError::InvalidTokenAccount)?;
+
+            associated_token::create(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: creator.to_account_info(),
+                    associated_token: creator_token_account.to_account_info(),
+                    authority: creator.to_account_info(),
+                    mint: token.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                },
+            ))?;
+
+            let token_key = token.key();
+            let swap_signer_seeds: &[&[&[u8]]] = &[
+                &[GLOBAL.as_bytes(), &[global_vault_bump]],
+                &[CURVE_VAULT.as_bytes(), token_key.as_ref(), &[curve_vault_bump]],
+            ];
+
+            let dev_buy = bonding_curve.swap(
+                global_config,
+                &mut global_token_account.to_account_info(),
+                &mut creator_token_account.to_account_info(),
+                global_vault,
+                &mut curve_vault.to_account_info(),
+                &mut self.team_wallet.to_account_info(),
+                initial_buy_lamports,
+                0,
+                min_tokens_out.unwrap_or(0), //  protects the creator from a sandwiched launch
+                None,
+                0,
+                creator,
+                swap_signer_seeds,
+                &self.token_program,
+                &self.system_program,
+            )?;
+            bonding_curve.creator_dev_buy_tokens = dev_buy.amount_out;
+            dev_buy_fees = Some(dev_buy.fees);
+        }
+
+        //  make sure the revoke took effect and no extra tokens exist before announcing the launch
+        check_launch_mint(&self.token.to_account_info(), token_supply)?;
+
+        global_config.record_launch();
+        global_config.store(&self.global_config)?;
+
+        emit_event!(self, launch_event);
+
+        if let Some(fees) = dev_buy_fees {
+            emit_event!(self, TradeBreakdownEvent::new(
+                self.creator.key(),
+                self.token.key(),
+                self.bonding_curve.key(),
+                0,
+                &fees,
+            ));
+
+            if global_config.accrues_team_fees() && fees.fee_amount > 0 {
+                emit_event!(self, TeamFeesAccruedEvent {
+                    mint: self.token.key(),
+                    bonding_curve: self.bonding_curve.key(),
+                    amount: fees.fee_amount,
+                    total_accrued: self.bonding_curve.team_fees_accrued,
+                });
+            }
+        }
 
         Ok(())
     }
 }
+
+//  creates the mint account, or adopts one that vanity tooling set up in an earlier instruction
+//  an adopted account has to be an all-zero, rent exempt account of the token program's mint
+//  size. the mint still signs the launch, so nobody else can launch a prepared address
+//  the launch is one instruction, a failure anywhere reverts the mint with it, so a retry
+//  with the same mint keypair starts clean and never sees a half-created launch
+fn prepare_mint_account<'info>(
+    mint: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent: &Rent,
+) -> Result<()> {
+    if mint.owner == &token::ID {
+        let data = mint.try_borrow_data()?;
+        if data.len() != Mint::LEN
+            || data.iter().any(|byte| *byte != 0)
+            || !rent.is_exempt(mint.lamports(), Mint::LEN)
+        {
+            msg!("mint {} is not an empty, rent exempt mint account", mint.key());
+            return Err(InvalidMint.into());
+        }
+
+        return Ok(());
+    }
+
+    if mint.owner != &system_program::ID || !mint.data_is_empty() {
+        msg!("mint {} is owned by {}", mint.key(), mint.owner);
+        return Err(InvalidMint.into());
+    }
+
+    let mint_rent = rent.minimum_balance(Mint::LEN);
+    if mint.lamports() == 0 {
+        return system_program::create_account(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: mint.clone(),
+                },
+            ),
+            mint_rent,
+            Mint::LEN as u64,
+            &token::ID,
+        );
+    }
+
+    //  `create_account` refuses an address that already holds lamports, build it in place
+    let shortfall = mint_rent.saturating_sub(mint.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: mint.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: mint.clone(),
+            },
+        ),
+        Mint::LEN as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new(
+            system_program.clone(),
+            system_program::Assign {
+                account_to_assign: mint.clone(),
+            },
+        ),
+        &token::ID,
+    )
+}
@@ -0,0 +1,79 @@
+use crate::{
+    constants::{BONDING_CURVE, CONFIG, GLOBAL},
+    emit_event,
+    errors::*,
+    events::CompleteEvent,
+    state::{bondingcurve::*, config::*},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct Finalize<'info> {
+    /// Keeper calling the instruction, anyone can finalize a ready curve
+    pub keeper: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    global_vault: UncheckedAccount<'info>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = token_mint,
+        associated_token::authority = global_vault
+    )]
+    global_token_account: Box<Account<'info, TokenAccount>>,
+}
+
+impl<'info> Finalize<'info> {
+    pub fn handler(&mut self) -> Result<()> {
+        let bonding_curve = &mut self.bonding_curve;
+
+        require!(
+            bonding_curve.ready_to_complete,
+            ContractError::CurveNotCompleted
+        );
+        require!(
+            !bonding_curve.is_completed,
+            ContractError::CurveAlreadyCompleted
+        );
+
+        self.global_config.check_migration_backlog()?;
+        self.global_config.record_completion();
+
+        bonding_curve.is_completed = true;
+        bonding_curve.mark_completed_at(Clock::get()?.unix_timestamp);
+        bonding_curve
+            .lock_migration_amounts(&self.global_config, self.global_token_account.amount)?;
+
+        emit_event!(self, CompleteEvent {
+            user: self.keeper.key(),
+            mint: self.token_mint.key(),
+            bonding_curve: bonding_curve.key(),
+            forced: false,
+            migration_sol_amount: bonding_curve.migration_sol_amount,
+            migration_token_amount: bonding_curve.migration_token_amount
+        });
+
+        Ok(())
+    }
+}
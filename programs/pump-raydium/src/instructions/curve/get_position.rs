@@ -0,0 +1,88 @@
+use crate::{
+    constants::{BONDING_CURVE, CONFIG, COST_BASIS},
+    emit_event,
+    errors::*,
+    events::PositionEvent,
+    state::{bondingcurve::*, config::*, cost_basis::*},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token,
+    token::{self, accessor, Mint},
+};
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct GetPosition<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: wallet whose position is read, doesn't have to sign
+    user: UncheckedAccount<'info>,
+
+    /// CHECK: canonical ata of (user, token_mint), may not exist yet
+    #[account(
+        address = associated_token::get_associated_token_address(&user.key(), &token_mint.key())
+            @ContractError::InvalidTokenAccount
+    )]
+    user_ata: UncheckedAccount<'info>,
+
+    /// CHECK: cost basis pda of the user, only exists once it bought with `loss_sell_grace` on
+    #[account(
+        seeds = [COST_BASIS.as_bytes(), token_mint.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    cost_basis: UncheckedAccount<'info>,
+}
+
+impl<'info> GetPosition<'info> {
+    //  view only, returns the unrealized P/L in lamports. wallets without a token account or
+    //  cost basis read as an empty position instead of failing
+    pub fn handler(&self) -> Result<i64> {
+        let tokens_held = if self.user_ata.owner == &token::ID && !self.user_ata.data_is_empty() {
+            accessor::amount(&self.user_ata)?
+        } else {
+            0
+        };
+        let cost_basis = if self.cost_basis.owner == &crate::ID {
+            CostBasis::try_deserialize(&mut &self.cost_basis.try_borrow_data()?[..])?
+        } else {
+            CostBasis::default()
+        };
+
+        //  what selling the whole balance would pay right now, fees included
+        let current_value = if tokens_held > 0 {
+            self.bonding_curve
+                .quote(&self.global_config, tokens_held, 1, Clock::get()?.slot)
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        //  tokens the wallet got outside the curve carry no cost
+        let cost = cost_basis.cost_of(tokens_held);
+        let unrealized_pnl = current_value as i64 - cost as i64;
+
+        emit_event!(self, PositionEvent {
+            user: self.user.key(),
+            mint: self.token_mint.key(),
+            tokens_held,
+            cost_basis: cost,
+            current_value,
+            unrealized_pnl,
+            realized_pnl: cost_basis.realized_pnl,
+        });
+
+        Ok(unrealized_pnl)
+    }
+}
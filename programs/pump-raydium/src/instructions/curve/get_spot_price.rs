@@ -0,0 +1,29 @@
+use crate::{
+    constants::{BONDING_CURVE, SPOT_PRICE_SCALE},
+    state::bondingcurve::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct GetSpotPrice<'info> {
+    #[account(
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+}
+
+impl<'info> GetSpotPrice<'info> {
+    //  view only, returns lamports per base token unit scaled by 10^SPOT_PRICE_SCALE
+    pub fn handler(&self) -> Result<u64> {
+        let spot_price = self
+            .bonding_curve
+            .spot_price_lamports_per_token_scaled(SPOT_PRICE_SCALE);
+        msg!("spot price: {spot_price} (scale {SPOT_PRICE_SCALE})");
+
+        Ok(spot_price)
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{
+    constants::{BONDING_CURVE, CONFIG, CURVE_VAULT, GLOBAL},
+    emit_event,
+    errors::*,
+    events::CurveVaultMigratedEvent,
+    state::{bondingcurve::*, config::*},
+    utils::{sol_transfer_from_user, sol_transfer_with_signer, validate_global_vault},
+};
+use anchor_lang::{prelude::*, system_program, Discriminator};
+use anchor_spl::token::Mint;
+
+//  moves a curve launched on the shared global vault onto its own vault
+//  those curves predate the current `BondingCurve` layout, so the account is rewritten too
+//  permissionless, the reserves can only move between the two program vaults
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct MigrateCurveVault<'info> {
+    /// Pays the curve vault rent and the rent of the larger curve account
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    /// CHECK: legacy curve, decoded as `LegacyBondingCurve` and rewritten in the current layout
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: AccountInfo<'info>,
+
+    /// CHECK: global vault pda which stores the SOL of shared-vault curves
+    #[account(
+        mut,
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    global_vault: AccountInfo<'info>,
+
+    /// CHECK: per-curve vault pda which stores this curve's SOL
+    #[account(
+        mut,
+        seeds = [CURVE_VAULT.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    curve_vault: AccountInfo<'info>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateCurveVault<'info> {
+    pub fn handler(&mut self, global_vault_bump: u8) -> Result<()> {
+        validate_global_vault(&self.global_vault, global_vault_bump)?;
+
+        let legacy_curve = self.load_legacy_curve()?;
+        let bonding_curve = legacy_curve.upgrade(&self.global_config);
+
+        //  grow the curve account to the size `create_bonding_curve` gives new curves
+        let curve_len = 8 + std::mem::size_of::<BondingCurve>();
+        let curve_rent = Rent::get()?.minimum_balance(curve_len);
+        let curve_shortfall = curve_rent.saturating_sub(self.bonding_curve.lamports());
+        if curve_shortfall > 0 {
+            sol_transfer_from_user(
+                &self.payer,
+                self.bonding_curve.clone(),
+                &self.system_program,
+                curve_shortfall,
+            )?;
+        }
+        self.bonding_curve.realloc(curve_len, true)?;
+        bonding_curve.try_serialize(&mut &mut self.bonding_curve.try_borrow_mut_data()?[..])?;
+
+        let rent = Rent::get()?.minimum_balance(0);
+        let shortfall = rent.saturating_sub(self.curve_vault.lamports());
+        if shortfall > 0 {
+            sol_transfer_from_user(
+                &self.payer,
+                self.curve_vault.clone(),
+                &self.system_program,
+                shortfall,
+            )?;
+        }
+
+        //  legacy curves never accrued team fees, the reserves are all the shared vault holds for them
+        let amount = bonding_curve.real_sol_reserves;
+        if amount > 0 {
+            let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+
+            sol_transfer_with_signer(
+                self.global_vault.clone(),
+                self.curve_vault.clone(),
+                &self.system_program,
+                signer_seeds,
+                amount,
+            )?;
+        }
+
+        emit_event!(self, CurveVaultMigratedEvent {
+            mint: self.token_mint.key(),
+            bonding_curve: self.bonding_curve.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    //  curves in the current layout already have their own vault, only the legacy one is accepted
+    fn load_legacy_curve(&self) -> Result<LegacyBondingCurve> {
+        if self.bonding_curve.owner != &crate::ID {
+            return Err(ContractError::ValueInvalid.into());
+        }
+
+        let data = self.bonding_curve.try_borrow_data()?;
+        if data.len() < 8 || data[0..8] != BondingCurve::DISCRIMINATOR {
+            return Err(ContractError::ValueInvalid.into());
+        }
+        if data.len() >= 8 + std::mem::size_of::<BondingCurve>() {
+            msg!("curve {} already uses its own vault", self.bonding_curve.key());
+            return Err(ContractError::ValueInvalid.into());
+        }
+
+        LegacyBondingCurve::deserialize(&mut &data[8..]).map_err(|_| {
+            msg!("curve {} could not be decoded", self.bonding_curve.key());
+            ContractError::ValueInvalid.into()
+        })
+    }
+}
@@ -1,3 +1,10 @@
 pub mod create_bonding_curve;
 pub use create_bonding_curve::*;
-pub mod swap;
\ No newline at end of file
+pub mod swap;
+pub mod finalize;
+pub mod transfer_creator;
+pub mod get_spot_price;
+pub mod migrate_curve_vault;
+pub mod quote_to_graduate;
+pub mod complete_buy;
+pub mod get_position;
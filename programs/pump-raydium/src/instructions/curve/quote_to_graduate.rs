@@ -0,0 +1,53 @@
+use crate::{
+    constants::{BONDING_CURVE, CONFIG},
+    emit_event,
+    errors::*,
+    events::GraduationQuoteEvent,
+    state::{bondingcurve::*, config::*},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct QuoteToGraduate<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+}
+
+impl<'info> QuoteToGraduate<'info> {
+    //  view only, returns the lamports of the buy that completes the curve, fees included
+    pub fn handler(&self) -> Result<u64> {
+        let bonding_curve = &self.bonding_curve;
+        require!(
+            !bonding_curve.is_completed && !bonding_curve.ready_to_complete,
+            ContractError::CurveAlreadyCompleted
+        );
+
+        let quote = bonding_curve
+            .quote_to_graduate(&self.global_config, Clock::get()?.slot)
+            .ok_or(ContractError::ArithmeticError)?;
+
+        emit_event!(self, GraduationQuoteEvent {
+            mint: self.token_mint.key(),
+            bonding_curve: self.bonding_curve.key(),
+            buy_amount: quote.buy_amount,
+            net_lamports: quote.net_lamports,
+            real_sol_reserves: self.bonding_curve.real_sol_reserves,
+            graduation_target: self.bonding_curve.graduation_target,
+        });
+
+        Ok(quote.buy_amount)
+    }
+}
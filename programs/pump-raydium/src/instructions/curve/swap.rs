@@ -1,15 +1,23 @@
 use anchor_lang::{system_program, prelude::*};
 use anchor_spl::{
     associated_token::{self, AssociatedToken},
-    token::{self, Mint, Token},
+    token::{self, Mint, Token, TokenAccount},
 };
 use crate::{
-    constants::{BONDING_CURVE, CONFIG, GLOBAL}, 
+    constants::{
+        BLOCKLIST, BONDING_CURVE, CONFIG, COST_BASIS, CURVE_VAULT, GLOBAL, SWAP_BUY_EXACT_OUT,
+    },
+    emit_event,
     errors::*, 
-    events::SwapEvent,
-    state::{bondingcurve::*,  config::*}
+    events::{
+        LossSellGraceEvent, ReserveSnapshot, SellReflectionEvent, SwapEvent, TeamFeesAccruedEvent,
+        TradeBreakdownEvent, TradeClampedEvent,
+    },
+    state::{bondingcurve::*,  config::*, cost_basis::*},
+    utils::{check_deadline, check_swap_direction, unwrap_user_wsol, validate_global_vault},
 };
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct Swap<'info> {
     #[account(
@@ -32,7 +40,7 @@ pub struct Swap<'info> {
     )]
     bonding_curve: Account<'info, BondingCurve>,
 
-    /// CHECK: global vault pda which stores SOL
+    /// CHECK: global vault pda, owns the curve tokens
     #[account(
         mut,
         seeds = [GLOBAL.as_bytes()],
@@ -40,6 +48,14 @@ pub struct Swap<'info> {
     )]
     pub global_vault: AccountInfo<'info>,
 
+    /// CHECK: per-curve vault pda which stores this curve's SOL
+    #[account(
+        mut,
+        seeds = [CURVE_VAULT.as_bytes(), token_mint.key().as_ref()],
+        bump,
+    )]
+    pub curve_vault: AccountInfo<'info>,
+
     pub token_mint: Box<Account<'info, Mint>>,
 
     /// CHECK: ata of global vault
@@ -55,22 +71,43 @@ pub struct Swap<'info> {
     )]
     global_ata: AccountInfo<'info>,
 
-    /// CHECK: ata of user
+    /// CHECK: canonical ata of (user, token_mint), any other token account is rejected
     #[account(
         mut,
-        seeds = [
-            user.key().as_ref(),
-            anchor_spl::token::spl_token::ID.as_ref(),
-            token_mint.key().as_ref(),
-        ],
-        bump,
-        seeds::program = anchor_spl::associated_token::ID
+        address = associated_token::get_associated_token_address(&user.key(), &token_mint.key())
+            @ContractError::InvalidTokenAccount,
+        constraint = user_ata.owner == &token::ID @ContractError::InvalidTokenAccount
     )]
     user_ata: AccountInfo<'info>,
 
     #[account(mut)]
     pub user: Signer<'info>,
 
+    /// user's WSOL account, closed into the curve vault by buys with `pay_with_wsol` set
+    #[account(
+        mut,
+        constraint = user_wsol_account.mint == spl_token::native_mint::ID @ContractError::InvalidWsolAccount,
+        constraint = user_wsol_account.owner == user.key() @ContractError::InvalidWsolAccount
+    )]
+    user_wsol_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// CHECK: blocklist pda of the user, required when the config enforces the blocklist
+    #[account(
+        seeds = [BLOCKLIST.as_bytes(), user.key().as_ref()],
+        bump
+    )]
+    blocked_wallet: Option<UncheckedAccount<'info>>,
+
+    /// cost basis of the user for this curve, required while `Config::tracks_positions`
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<CostBasis>(),
+        seeds = [COST_BASIS.as_bytes(), token_mint.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    cost_basis: Option<Box<Account<'info, CostBasis>>>,
+
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
 
@@ -82,18 +119,31 @@ pub struct Swap<'info> {
 }
 
 impl<'info> Swap<'info> { 
-pub fn handler(&mut self, amount: u64, direction: u8, minimum_receive_amount: u64,global_vault_bump:u8) -> Result<u64> {
+pub fn handler(&mut self, amount: u64, direction: u8, minimum_receive_amount: u64, pay_with_wsol: bool, clamp_to_slippage: bool, deadline: Option<i64>, max_input: Option<u64>, global_vault_bump:u8, curve_vault_bump: u8) -> Result<u64> {
+    validate_global_vault(&self.global_vault, global_vault_bump)?;
+    check_deadline(deadline, Clock::get()?.unix_timestamp)?;
+    check_swap_direction(direction, max_input)?;
     let bonding_curve = &mut self.bonding_curve;
 
     //  check curve is not completed
-    // require!(
-    //     bonding_curve.is_completed == false,
-    //     ContractError::CurveAlreadyCompleted
-    // );
+    require!(
+        !bonding_curve.is_completed && !bonding_curve.ready_to_complete,
+        ContractError::CurveAlreadyCompleted
+    );
+
+    if self.global_config.enforce_blocklist {
+        let blocked_wallet = self
+            .blocked_wallet
+            .as_ref()
+            .ok_or(ContractError::WalletBlocked)?;
+        require!(blocked_wallet.data_is_empty(), ContractError::WalletBlocked);
+    }
 
-    let source = &mut self.global_vault.to_account_info();
+    //  curves launched on the shared vault have to move their sol first
+    require!(bonding_curve.uses_curve_vault, ContractError::CurveVaultNotMigrated);
+
+    let source = &mut self.curve_vault.to_account_info();
 
-    let token = &mut self.token_mint;
     let team_wallet = &mut self.team_wallet;
     let user_ata = &mut self.user_ata;
 
@@ -113,21 +163,106 @@ pub fn handler(&mut self, amount: u64, direction: u8, minimum_receive_amount: u6
     //     ))?;
     // }
 
-    let signer_seeds: &[&[&[u8]]] = &[&[
-        GLOBAL.as_bytes(),
-        &[global_vault_bump],
-    ]];
+    let token_mint_key = self.token_mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[
+        &[GLOBAL.as_bytes(), &[global_vault_bump]],
+        &[CURVE_VAULT.as_bytes(), token_mint_key.as_ref(), &[curve_vault_bump]],
+    ];
+
+    //  exact-output buys are priced here and then run as a regular buy of that size, the
+    //  requested tokens become the min out so rounding can only ever give the buyer more
+    let (amount, direction, minimum_receive_amount) = if direction == SWAP_BUY_EXACT_OUT {
+        let buy_amount = bonding_curve.buy_amount_for_tokens(
+            &self.global_config,
+            amount,
+            max_input.ok_or(ContractError::InvalidAmount)?,
+            Clock::get()?.slot,
+        )?;
+        (buy_amount, 0, amount.max(minimum_receive_amount))
+    } else {
+        (amount, direction, minimum_receive_amount)
+    };
+
+    //  shrink the trade instead of reverting when the full size would miss the slippage bound
+    //  the minimum output is scaled down with the size so the requested rate is kept
+    let requested_amount = amount;
+    let (amount, minimum_receive_amount) = if clamp_to_slippage && amount > 0 {
+        let executed_amount = bonding_curve.clamp_to_slippage(
+            &self.global_config,
+            amount,
+            direction,
+            minimum_receive_amount,
+            Clock::get()?.slot,
+        );
+        require!(executed_amount > 0, ContractError::SlippageExceeded);
+
+        let scaled_minimum =
+            (minimum_receive_amount as u128 * executed_amount as u128 / amount as u128) as u64;
+        (executed_amount, scaled_minimum)
+    } else {
+        (amount, minimum_receive_amount)
+    };
+
+    if direction == 1 {
+        bonding_curve.check_creator_hold(
+            &self.global_config,
+            &self.user.key(),
+            token::accessor::amount(user_ata)?,
+            amount,
+        )?;
+    }
+    let reflected_amount = if direction == 1 {
+        bonding_curve.sell_reflection(&self.global_config, amount)
+    } else {
+        0
+    };
 
-    let amount_out = bonding_curve.swap(
-        &*self.global_config,
-        token.as_ref(),
+    let cost_basis = self.cost_basis.as_deref().map(|cost_basis| &**cost_basis);
+    bonding_curve.open_position(&self.global_config, cost_basis, direction)?;
+    let loss_sell_fee_bps = match &self.cost_basis {
+        Some(cost_basis) if direction == 1 => {
+            bonding_curve.loss_sell_fee_bps(&self.global_config, cost_basis, amount)
+        }
+        _ => None,
+    };
+    //  buys paid in WSOL unwrap the user's WSOL account straight into the curve vault, the buy is
+    //  paid from there and whatever it doesn't spend goes back to the user as SOL
+    let prepaid = if pay_with_wsol {
+        require!(direction != 1, ContractError::InvalidWsolAccount);
+        let user_wsol_account = self
+            .user_wsol_account
+            .as_ref()
+            .ok_or(ContractError::InvalidWsolAccount)?;
+        if user_wsol_account.amount < amount {
+            msg!("WSOL balance {} doesn't cover the buy of {amount}", user_wsol_account.amount);
+            return err!(ContractError::InsufficientSol);
+        }
+
+        unwrap_user_wsol(
+            user_wsol_account.to_account_info(),
+            source.clone(),
+            &self.user,
+            &self.token_program,
+        )?
+    } else {
+        0
+    };
+    //  unwrapped WSOL counts as the user's lamports, so the cost basis sees what the buy took
+    let user_lamports_before = self.user.lamports() + prepaid;
+    let ath_price_before = bonding_curve.ath_price_scaled;
+
+    let TradeResult { amount_out, fees } = bonding_curve.swap(
+        &self.global_config,
         &mut self.global_ata,
         user_ata,
+        &self.global_vault,
         source,
         team_wallet,
         amount,
         direction,
         minimum_receive_amount,
+        loss_sell_fee_bps,
+        prepaid,
 
         &self.user,
         signer_seeds,
@@ -136,23 +271,121 @@ pub fn handler(&mut self, amount: u64, direction: u8, minimum_receive_amount: u6
         &self.system_program,
     )?;
 
-    emit!(
-        SwapEvent {
-            user: self.user.key(),
-            mint: self.token_mint.key(),
-            bonding_curve: bonding_curve.key(),
+    if self.global_config.tracks_positions() {
+        if let Some(cost_basis) = self.cost_basis.as_mut() {
+            cost_basis.record_trade(
+                self.user.key(),
+                self.token_mint.key(),
+                direction,
+                amount,
+                amount_out,
+                user_lamports_before.saturating_sub(self.user.lamports()),
+            );
+        }
+    }
 
-            amount_in: amount,
+    emit_event!(self,
+        SwapEvent::new(
+            self.user.key(),
+            self.token_mint.key(),
+            bonding_curve.key(),
+            bonding_curve,
+            amount,
             direction,
             minimum_receive_amount,
             amount_out,
+            Clock::get()?.slot,
+            ath_price_before,
+        )
+    );
 
-            virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
-            virtual_token_reserves: bonding_curve.virtual_token_reserves
-        }
+    emit_event!(self,
+        TradeBreakdownEvent::new(
+            self.user.key(),
+            self.token_mint.key(),
+            bonding_curve.key(),
+            direction,
+            &fees,
+        )
     );
+
+    if self.global_config.accrues_team_fees() && fees.fee_amount > 0 {
+        emit_event!(self,
+            TeamFeesAccruedEvent {
+                mint: self.token_mint.key(),
+                bonding_curve: bonding_curve.key(),
+                amount: fees.fee_amount,
+                total_accrued: bonding_curve.team_fees_accrued,
+            }
+        );
+    }
+
+    if let Some(fee_bps) = loss_sell_fee_bps {
+        emit_event!(self,
+            LossSellGraceEvent {
+                user: self.user.key(),
+                mint: self.token_mint.key(),
+                bonding_curve: bonding_curve.key(),
+                fee_bps,
+            }
+        );
+    }
+
+    if reflected_amount > 0 {
+        emit_event!(self,
+            SellReflectionEvent {
+                user: self.user.key(),
+                mint: self.token_mint.key(),
+                bonding_curve: bonding_curve.key(),
+                amount: reflected_amount,
+            }
+        );
+    }
+
+    if amount < requested_amount {
+        emit_event!(self,
+            TradeClampedEvent {
+                user: self.user.key(),
+                mint: self.token_mint.key(),
+                bonding_curve: bonding_curve.key(),
+                direction,
+                requested_amount,
+                executed_amount: amount,
+                amount_out,
+            }
+        );
+    }
+
+    if self.global_config.emit_reserves {
+        emit_event!(self,
+            ReserveSnapshot {
+                mint: self.token_mint.key(),
+                virtual_sol: bonding_curve.virtual_sol_reserves,
+                virtual_token: bonding_curve.virtual_token_reserves,
+                timestamp: Clock::get()?.unix_timestamp
+            }
+        );
+    }
     
     Ok(amount_out)
 }
 
+//  sells the whole token balance as it is when the transaction executes, a client-side amount
+//  can be stale by then. same path as a regular sell, an empty balance fails with `InvalidAmount`
+pub fn sell_all_handler(&mut self, minimum_receive_amount: u64, deadline: Option<i64>, global_vault_bump: u8, curve_vault_bump: u8) -> Result<u64> {
+    let amount = token::accessor::amount(&self.user_ata)?;
+
+    self.handler(
+        amount,
+        1,
+        minimum_receive_amount,
+        false,
+        false,
+        deadline,
+        None,
+        global_vault_bump,
+        curve_vault_bump,
+    )
+}
+
 }
\ No newline at end of file
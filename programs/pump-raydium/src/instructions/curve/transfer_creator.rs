@@ -0,0 +1,44 @@
+use crate::{
+    constants::BONDING_CURVE,
+    emit_event,
+    errors::*,
+    events::CreatorTransferEvent,
+    state::bondingcurve::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct TransferCreator<'info> {
+    /// Current creator of the bonding curve
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        has_one = creator @ContractError::IncorrectAuthority
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+}
+
+impl<'info> TransferCreator<'info> {
+    pub fn handler(&mut self, new_creator: Pubkey) -> Result<()> {
+        let bonding_curve = &mut self.bonding_curve;
+
+        //  creator fees are not accrued on the curve yet, so there is nothing to settle
+        bonding_curve.creator = new_creator;
+
+        emit_event!(self, CreatorTransferEvent {
+            mint: self.token_mint.key(),
+            bonding_curve: bonding_curve.key(),
+            old_creator: self.creator.key(),
+            new_creator,
+        });
+
+        Ok(())
+    }
+}
@@ -1,17 +1,24 @@
 use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
-use anchor_spl::token::{burn, Burn, TokenAccount};
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
 
 use crate::{
     amm_instruction,
-    constants::{BONDING_CURVE, CONFIG, GLOBAL},
+    constants::{
+        BONDING_CURVE, CONFIG, CURVE_VAULT, GLOBAL, MIGRATION_TARGET_RAYDIUM, OPENBOOK_PROGRAM_ID,
+    },
+    emit_event,
     errors::ContractError,
-    events::MigrateEvent,
+    events::{BurnEvent, MigrationEvent, MigrationSurplusEvent},
     state::{bondingcurve::*, config::*},
-    utils::sol_transfer_with_signer,
+    utils::{
+        invoke_raydium_initialize2, lp_account_balance, pool_vault_amount,
+        sol_transfer_with_signer, validate_global_vault,
+    },
 };
 
 use spl_token::instruction::sync_native;
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct Migrate<'info> {
     /// CHECK: Safe
@@ -22,6 +29,7 @@ pub struct Migrate<'info> {
     team_wallet: UncheckedAccount<'info>,
 
     #[account(
+        mut,
         seeds = [CONFIG.as_bytes()],
         bump,
     )]
@@ -42,12 +50,19 @@ pub struct Migrate<'info> {
     )]
     global_vault: UncheckedAccount<'info>,
 
-    /// CHECK: Safe
+    /// CHECK: per-curve vault pda which stores this curve's SOL
+    #[account(
+        mut,
+        seeds = [CURVE_VAULT.as_bytes(), coin_mint.key().as_ref()],
+        bump,
+    )]
+    curve_vault: UncheckedAccount<'info>,
+
+    /// CHECK: the raydium amm program, every pool pda below is derived from it
+    #[account(address = amm_instruction::ID @ContractError::InvalidRaydiumProgram)]
     amm_program: UncheckedAccount<'info>,
 
-    /// CHECK: Safe. The spl token program
-    // token_program: Program<'info, Token>,
-    token_program: UncheckedAccount<'info>,
+    token_program: Program<'info, Token>,
 
     /// CHECK: Safe. The associated token program
     // associated_token_program: Program<'info, AssociatedToken>,
@@ -107,11 +122,11 @@ pub struct Migrate<'info> {
     )]
     lp_mint: UncheckedAccount<'info>,
 
-    ///CHECK:Safe
     #[account(mut)]
-    coin_mint: UncheckedAccount<'info>,
+    coin_mint: Box<Account<'info, Mint>>, //  raydium gives the LP mint the coin's decimals
 
     /// CHECK: Safe. Pc mint account
+    #[account(address = spl_token::native_mint::ID)]
     pc_mint: UncheckedAccount<'info>,
     /// CHECK: Safe
     #[account(
@@ -152,7 +167,7 @@ pub struct Migrate<'info> {
     )]
     target_orders: UncheckedAccount<'info>,
 
-    /// CHECK: Safe
+    /// CHECK: raydium's amm config, pinned by its seeds under the fixed amm program
     #[account(
         mut,
         seeds = [b"amm_config_account_seed"],
@@ -162,6 +177,7 @@ pub struct Migrate<'info> {
     amm_config: UncheckedAccount<'info>,
 
     /// CHECK: Safe. OpenBook program.
+    #[account(address = OPENBOOK_PROGRAM_ID @ContractError::InvalidRaydiumProgram)]
     market_program: UncheckedAccount<'info>,
 
     /// CHECK: Safe. OpenBook market. OpenBook program is the owner.
@@ -190,7 +206,8 @@ pub struct Migrate<'info> {
     )]
     global_wsol_account: Box<Account<'info, TokenAccount>>,
 
-    /// CHECK: Safe. lp token account of global_vault
+    /// CHECK: the global vault's LP ATA, created by `initialize2`
+    /// `lp_balance` checks its mint and authority
     #[account(
         mut,
         seeds = [
@@ -205,21 +222,280 @@ pub struct Migrate<'info> {
 }
 
 impl<'info> Migrate<'info> {
-    pub fn process(&mut self, nonce: u8, global_vault_bump: u8) -> Result<()> {
+    pub fn process(
+        &mut self,
+        nonce: u8,
+        burn_unsold: bool,
+        global_vault_bump: u8,
+        curve_vault_bump: u8,
+    ) -> Result<()> {
+        validate_global_vault(&self.global_vault, global_vault_bump)?;
         let bonding_curve = &mut self.bonding_curve;
 
         //  check curve is completed
         require!(
-            bonding_curve.is_completed == true,
+            bonding_curve.is_completed,
             ContractError::CurveNotCompleted
         );
+        require!(
+            !bonding_curve.is_migrated,
+            ContractError::AmmAlreadyExists
+        );
+        bonding_curve.check_migration_delay(&self.global_config, Clock::get()?.unix_timestamp)?;
 
         require!(
-            bonding_curve.real_sol_reserves > self.global_config.curve_limit,
+            bonding_curve.real_sol_reserves >= bonding_curve.graduation_target,
             ContractError::ArithmeticError
         );
 
-        ///Telegram: [enlomy](https://t.me/enlomy)
+        //  the curve picked its dex at launch, this instruction carries the raydium accounts
+        match bonding_curve.migration_target {
+            MIGRATION_TARGET_RAYDIUM => {}
+            _ => {
+                msg!("curve graduates to target {}, not raydium", bonding_curve.migration_target);
+                return err!(ContractError::UnsupportedMigrationTarget);
+            }
+        }
+
+        let MigrationPlan {
+            amounts,
+            unsold_burn_amount,
+            lp_locked,
+        } = bonding_curve.migration_plan(
+            &self.global_config,
+            burn_unsold,
+            self.coin_mint.decimals,
+        )?;
+
+        //  the instruction is atomic, but the intended amounts in the logs make failed
+        //  migrations possible to reconstruct from the transaction alone
+        msg!(
+            "migrating {} into pool {}: {} lamports, {} tokens, {} fee, {} burned",
+            self.coin_mint.key(),
+            self.amm.key(),
+            amounts.sol_amount,
+            amounts.token_amount,
+            amounts.fee_amount,
+            amounts.burn_amount
+        );
+
+        let coin_mint_key = self.coin_mint.key();
+        let curve_vault_seeds: &[&[&[u8]]] = &[&[
+            CURVE_VAULT.as_bytes(),
+            coin_mint_key.as_ref(),
+            &[curve_vault_bump],
+        ]];
+
+        //  move the pool deposit out of the curve vault into the global WSOL account
+        //  curves still on the shared vault had their sol wrapped with `wrap_sol` instead
+        if self.bonding_curve.uses_curve_vault {
+            sol_transfer_with_signer(
+                self.curve_vault.to_account_info(),
+                self.global_wsol_account.to_account_info(),
+                &self.system_program,
+                curve_vault_seeds,
+                amounts.sol_amount,
+            )?;
+
+            invoke_signed(
+                &sync_native(&spl_token::ID, &self.global_wsol_account.key())?,
+                &[
+                    self.global_wsol_account.to_account_info(),
+                    self.token_program.to_account_info(),
+                ],
+                &[],
+            )?;
+        }
+
+        self.initialize_pool(nonce, &amounts, global_vault_bump)?;
+        //  the curve is only marked migrated, and the event only emitted, once its pool exists
+        require_keys_eq!(
+            *self.amm.owner,
+            self.amm_program.key(),
+            ContractError::RaydiumCpiFailed
+        );
+        self.check_pool_deposit(&amounts)?;
+
+        //  the fee and the returned surplus are paid out only on top of a landed deposit
+        if self.bonding_curve.uses_curve_vault {
+            sol_transfer_with_signer(
+                self.curve_vault.to_account_info(),
+                self.team_wallet.to_account_info(),
+                &self.system_program,
+                curve_vault_seeds,
+                amounts.fee_amount + amounts.surplus_amount,
+            )?;
+
+            let tokens_burned = amounts.burn_amount - unsold_burn_amount;
+            if amounts.surplus_amount > 0 || tokens_burned > 0 {
+                emit_event!(self, MigrationSurplusEvent {
+                    mint: self.coin_mint.key(),
+                    bonding_curve: self.bonding_curve.key(),
+                    mode: self.global_config.migration_surplus,
+                    sol_returned: amounts.surplus_amount,
+                    tokens_burned,
+                });
+            }
+        }
+
+        //  deflationary graduation, burn what the pool didn't take once the deposit has landed
+        if amounts.burn_amount > 0 {
+            let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+
+            burn(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    Burn {
+                        mint: self.coin_mint.to_account_info(),
+                        from: self.global_token_account.to_account_info(),
+                        authority: self.global_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amounts.burn_amount,
+            )?;
+
+            emit_event!(self, BurnEvent {
+                mint: self.coin_mint.key(),
+                bonding_curve: self.bonding_curve.key(),
+                amount: amounts.burn_amount,
+            });
+        }
+
+        self.lock_minimum_liquidity(lp_locked, global_vault_bump)?;
+
+        self.bonding_curve.is_migrated = true;
+        self.global_config.record_migration();
+
+        emit_event!(self, MigrationEvent::new(
+            self.coin_mint.key(),
+            self.amm.key(),
+            self.lp_balance()?,
+            &amounts,
+            lp_locked,
+            Clock::get()?.unix_timestamp,
+        ));
+
         Ok(())
     }
+
+    //  opens the raydium pool with the curve's liquidity. the global vault signs as the depositor,
+    //  so it also funds the pool accounts and raydium's creation fee
+    fn initialize_pool(
+        &self,
+        nonce: u8,
+        amounts: &MigrationAmounts,
+        global_vault_bump: u8,
+    ) -> Result<()> {
+        let initialize_ix = amm_instruction::initialize2(
+            self.amm_program.key,
+            self.amm.key,
+            self.amm_authority.key,
+            self.amm_open_orders.key,
+            self.lp_mint.key,
+            &self.coin_mint.key(),
+            self.pc_mint.key,
+            self.coin_vault.key,
+            self.pc_vault.key,
+            self.target_orders.key,
+            self.amm_config.key,
+            self.fee_destination.key,
+            self.market_program.key,
+            self.market.key,
+            self.global_vault.key,
+            &self.global_token_account.key(),
+            &self.global_wsol_account.key(),
+            self.global_lp_account.key,
+            nonce,
+            Clock::get()?.unix_timestamp as u64,
+            amounts.sol_amount,
+            amounts.token_amount,
+        )?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+        invoke_raydium_initialize2(
+            &initialize_ix,
+            &[
+                self.token_program.to_account_info(),
+                self.associated_token_program.to_account_info(),
+                self.system_program.to_account_info(),
+                self.sysvar_rent.to_account_info(),
+                self.amm.to_account_info(),
+                self.amm_authority.to_account_info(),
+                self.amm_open_orders.to_account_info(),
+                self.lp_mint.to_account_info(),
+                self.coin_mint.to_account_info(),
+                self.pc_mint.to_account_info(),
+                self.coin_vault.to_account_info(),
+                self.pc_vault.to_account_info(),
+                self.target_orders.to_account_info(),
+                self.amm_config.to_account_info(),
+                self.fee_destination.to_account_info(),
+                self.market_program.to_account_info(),
+                self.market.to_account_info(),
+                self.global_vault.to_account_info(),
+                self.global_token_account.to_account_info(),
+                self.global_wsol_account.to_account_info(),
+                self.global_lp_account.to_account_info(),
+                self.amm_program.to_account_info(),
+            ],
+            signer_seeds,
+            &self.amm.to_account_info(),
+            amounts.token_amount,
+            amounts.sol_amount,
+            self.coin_mint.decimals,
+        )
+    }
+
+    //  the pool vaults hold exactly what was deposited, with `add_to_pool` that includes the surplus
+    fn check_pool_deposit(&self, amounts: &MigrationAmounts) -> Result<()> {
+        let pool_sol = pool_vault_amount(&self.pc_vault, self.pc_mint.key)?;
+        let pool_tokens = pool_vault_amount(&self.coin_vault, &self.coin_mint.key())?;
+        if pool_sol != amounts.sol_amount || pool_tokens != amounts.token_amount {
+            msg!(
+                "pool {} holds {pool_sol} lamports and {pool_tokens} tokens, expected {} and {}",
+                self.amm.key(),
+                amounts.sol_amount,
+                amounts.token_amount
+            );
+            return Err(ContractError::RaydiumCpiFailed.into());
+        }
+
+        Ok(())
+    }
+
+    //  burns a fixed slice of the LP tokens `initialize2` just minted, like the uniswap minimum
+    //  liquidity, so the pool always keeps some reserves and a first depositor can't skew the share price
+    fn lock_minimum_liquidity(&self, lp_locked: u64, global_vault_bump: u8) -> Result<()> {
+        if lp_locked == 0 {
+            return Ok(());
+        }
+
+        let lp_balance = self.lp_balance()?;
+        if lp_balance < lp_locked {
+            msg!("global LP account holds {lp_balance} LP tokens, expected at least {lp_locked}");
+            return Err(ContractError::InsufficientLpToLock.into());
+        }
+
+        let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+        burn(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.lp_mint.to_account_info(),
+                    from: self.global_lp_account.to_account_info(),
+                    authority: self.global_vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            lp_locked,
+        )?;
+
+        Ok(())
+    }
+
+    //  LP tokens held by the global vault, 0 while its LP account doesn't exist
+    fn lp_balance(&self) -> Result<u64> {
+        lp_account_balance(&self.global_lp_account, self.lp_mint.key, self.global_vault.key)
+    }
 }
@@ -1,2 +1,5 @@
 pub mod migrate;
 pub use migrate::*;
+pub mod simulate_migrate;
+pub mod sweep_dust;
+pub mod seed_liquidity;
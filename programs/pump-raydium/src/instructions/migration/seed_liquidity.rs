@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use crate::{
+    constants::{BOOTSTRAP_LIQUIDITY, CONFIG},
+    emit_event,
+    errors::ContractError,
+    events::LiquiditySeededEvent,
+    state::{bootstrap_liquidity::*, config::*},
+};
+
+//  releases the tokens set aside at launch so the team can open an early dex pool,
+//  the curve reserves are not touched
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SeedLiquidity<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        has_one = authority @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [BOOTSTRAP_LIQUIDITY.as_bytes(), coin_mint.key().as_ref()],
+        bump
+    )]
+    bootstrap_liquidity: Box<Account<'info, BootstrapLiquidity>>,
+
+    coin_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = coin_mint,
+        associated_token::authority = bootstrap_liquidity
+    )]
+    bootstrap_token_account: Box<Account<'info, TokenAccount>>,
+
+    //  token account the pool is created from
+    #[account(
+        mut,
+        token::mint = coin_mint,
+    )]
+    destination: Box<Account<'info, TokenAccount>>,
+
+    token_program: Program<'info, Token>,
+}
+
+impl<'info> SeedLiquidity<'info> {
+    pub fn process(&mut self, bootstrap_liquidity_bump: u8) -> Result<()> {
+        require!(
+            !self.bootstrap_liquidity.seeded,
+            ContractError::AmmAlreadyExists
+        );
+
+        let amount = self.bootstrap_token_account.amount;
+        let coin_mint = self.coin_mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            BOOTSTRAP_LIQUIDITY.as_bytes(),
+            coin_mint.as_ref(),
+            &[bootstrap_liquidity_bump],
+        ]];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                token::Transfer {
+                    from: self.bootstrap_token_account.to_account_info(),
+                    to: self.destination.to_account_info(),
+                    authority: self.bootstrap_liquidity.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+        self.bootstrap_liquidity.seeded = true;
+
+        emit_event!(self, LiquiditySeededEvent {
+            mint: coin_mint,
+            destination: self.destination.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::{
+    constants::{BONDING_CURVE, CONFIG, GLOBAL},
+    emit_event,
+    errors::ContractError,
+    events::MigrationPreviewEvent,
+    state::{bondingcurve::*, config::*},
+};
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SimulateMigrate<'info> {
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [BONDING_CURVE.as_bytes(), &coin_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    global_vault: UncheckedAccount<'info>,
+
+    coin_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = coin_mint,
+        associated_token::authority = global_vault
+    )]
+    global_token_account: Box<Account<'info, TokenAccount>>,
+}
+
+impl<'info> SimulateMigrate<'info> {
+    //  `burn_unsold` as it would be passed to `migrate`
+    pub fn process(&self, burn_unsold: bool) -> Result<()> {
+        let bonding_curve = &self.bonding_curve;
+
+        require!(
+            bonding_curve.is_completed,
+            ContractError::CurveNotCompleted
+        );
+
+        let MigrationPlan {
+            amounts,
+            lp_locked,
+            ..
+        } = bonding_curve.migration_plan(
+            &self.global_config,
+            burn_unsold,
+            self.coin_mint.decimals,
+        )?;
+
+        //  lamports per whole token, scaled by 1e9 to keep sub-lamport prices
+        let initial_price_scaled = (amounts.sol_amount as u128)
+            .checked_mul(10u128.pow(self.coin_mint.decimals as u32))
+            .and_then(|value| value.checked_mul(1_000_000_000))
+            .and_then(|value| value.checked_div(amounts.token_amount as u128))
+            .and_then(|value| u64::try_from(value).ok())
+            .unwrap_or(0);
+
+        emit_event!(self, MigrationPreviewEvent {
+            mint: self.coin_mint.key(),
+            bonding_curve: bonding_curve.key(),
+            sol_amount: amounts.sol_amount,
+            token_amount: amounts.token_amount,
+            fee_amount: amounts.fee_amount,
+            burn_amount: amounts.burn_amount,
+            surplus_amount: amounts.surplus_amount,
+            lp_locked,
+            initial_price_scaled,
+        });
+
+        Ok(())
+    }
+}
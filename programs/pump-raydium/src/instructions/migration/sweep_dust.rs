@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use crate::{
+    constants::{BONDING_CURVE, CONFIG, GLOBAL},
+    emit_event,
+    errors::ContractError,
+    events::DustSweptEvent,
+    state::{bondingcurve::*, config::*},
+    utils::validate_global_vault,
+};
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SweepDust<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [CONFIG.as_bytes()],
+        bump,
+        has_one = authority @ContractError::IncorrectAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        seeds = [BONDING_CURVE.as_bytes(), &coin_mint.key().to_bytes()],
+        bump
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    /// CHECK: global vault pda which stores SOL
+    #[account(
+        seeds = [GLOBAL.as_bytes()],
+        bump,
+    )]
+    global_vault: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    coin_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = coin_mint,
+        associated_token::authority = global_vault
+    )]
+    global_token_account: Box<Account<'info, TokenAccount>>,
+
+    token_program: Program<'info, Token>,
+}
+
+impl<'info> SweepDust<'info> {
+    //  burns the tokens left in the global token account after migration
+    pub fn process(&mut self, global_vault_bump: u8) -> Result<()> {
+        validate_global_vault(&self.global_vault, global_vault_bump)?;
+        require!(
+            self.bonding_curve.is_migrated,
+            ContractError::NotMigrated
+        );
+
+        let amount = self.global_token_account.amount;
+        if amount > 0 {
+            let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+
+            token::burn(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    token::Burn {
+                        mint: self.coin_mint.to_account_info(),
+                        from: self.global_token_account.to_account_info(),
+                        authority: self.global_vault.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            )?;
+        }
+
+        emit_event!(self, DustSweptEvent {
+            mint: self.coin_mint.key(),
+            bonding_curve: self.bonding_curve.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
@@ -4,11 +4,18 @@ pub mod constants;
 pub mod errors;
 pub mod events;
 pub mod instructions;
+pub mod pda;
 pub mod state;
 pub mod utils;
 
-use instructions::{configure::*, create_bonding_curve::*, migrate::*, swap::*};
-use state::config::*;
+use instructions::{
+    admin_set_reserves::*, blocklist::*, claim_team_fees::*, close_config::*, complete_buy::*,
+    configure::*, create_bonding_curve::*, initialize_config::*, finalize::*, force_complete::*, get_config::*,
+    get_position::*, get_spot_price::*, migrate::*, migrate_config::*, migrate_curve_vault::*,
+    quote_to_graduate::*, seed_liquidity::*, set_trading::*, simulate_migrate::*,
+    swap::*, sweep_dust::*, top_up_vault::*, transfer_creator::*, vault_wsol::*,
+};
+use state::{config::*, launch_params::*};
 
 declare_id!("BjcqoRYZuWuGu5nHSrEyi5DFZKg51xNSP9RP7nEYp75j");
 
@@ -16,32 +23,70 @@ declare_id!("BjcqoRYZuWuGu5nHSrEyi5DFZKg51xNSP9RP7nEYp75j");
 pub mod pump_raydium {
     use super::*;
 
+    pub fn initialize_config(ctx: Context<InitializeConfig>, new_config: Config) -> Result<()> {
+        ctx.accounts.handler(new_config)
+    }
+
     pub fn configure(ctx: Context<Configure>, new_config: Config) -> Result<()> {
-        ctx.accounts.handler(new_config, ctx.bumps.config)
+        ctx.accounts.handler(new_config)
     }
 
-    pub fn create_bonding_curve(
-        ctx: Context<CreateBondingCurve>,
+    //  one time upgrade of a config stored before the `version` field
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    pub fn close_config(ctx: Context<CloseConfig>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    //  view of the stored config, run it through a simulation
+    pub fn get_config(ctx: Context<GetConfig>) -> Result<Config> {
+        ctx.accounts.handler()
+    }
+
+    //  wraps vault SOL into the global WSOL account
+    pub fn wrap_sol(ctx: Context<WrapSol>, amount: u64) -> Result<()> {
+        ctx.accounts.handler(amount, ctx.bumps.global_vault)
+    }
+
+    //  unwraps the global WSOL account back into the vault
+    pub fn unwrap_sol(ctx: Context<UnwrapSol>) -> Result<()> {
+        ctx.accounts.handler(ctx.bumps.global_vault)
+    }
+
+    //  anyone can restore the global vault's rent exemption
+    pub fn top_up_vault(ctx: Context<TopUpVault>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    //  anyone can pay a curve's accrued team fees out to the team wallet
+    pub fn claim_team_fees(ctx: Context<ClaimTeamFees>) -> Result<u64> {
+        ctx.accounts
+            .handler(ctx.bumps.global_vault, ctx.bumps.curve_vault)
+    }
+
+    pub fn set_trading(
+        ctx: Context<SetTrading>,
+        buys_enabled: bool,
+        sells_enabled: bool,
+    ) -> Result<()> {
+        ctx.accounts.handler(buys_enabled, sells_enabled)
+    }
 
-        // bonding curve config
-        decimals: u8,
-        token_supply: u64,
-        virtual_lamport_reserves: u64,
+    pub fn block_wallet(ctx: Context<BlockWallet>, wallet: Pubkey) -> Result<()> {
+        ctx.accounts.handler(wallet)
+    }
+
+    pub fn unblock_wallet(ctx: Context<UnblockWallet>, wallet: Pubkey) -> Result<()> {
+        ctx.accounts.handler(wallet)
+    }
 
-        //  metadata
-        name: String,
-        symbol: String,
-        uri: String,
+    pub fn create_bonding_curve(
+        ctx: Context<CreateBondingCurve>,
+        params: TokenLaunchParams,
     ) -> Result<()> {
-        ctx.accounts.handler(
-            decimals,
-            token_supply,
-            virtual_lamport_reserves,
-            name,
-            symbol,
-            uri,
-            ctx.bumps.global_vault,
-        )
+        ctx.accounts.handler(params, ctx.bumps.global_vault, ctx.bumps.curve_vault)
     }
 
     pub fn swap(
@@ -49,19 +94,110 @@ pub mod pump_raydium {
         amount: u64,
         direction: u8,
         minimum_receive_amount: u64,
+        pay_with_wsol: bool,
+        clamp_to_slippage: bool,
+        deadline: Option<i64>,
+        max_input: Option<u64>, //  lamports cap of `SWAP_BUY_EXACT_OUT` buys, fees included
     ) -> Result<u64> {
         ctx.accounts.handler(
             amount,
             direction,
             minimum_receive_amount,
+            pay_with_wsol,
+            clamp_to_slippage,
+            deadline,
+            max_input,
             ctx.bumps.global_vault,
+            ctx.bumps.curve_vault,
         )
     }
 
+    //  sells the user's entire token balance, read at execution time
+    pub fn sell_all(
+        ctx: Context<Swap>,
+        minimum_receive_amount: u64,
+        deadline: Option<i64>,
+    ) -> Result<u64> {
+        ctx.accounts.sell_all_handler(
+            minimum_receive_amount,
+            deadline,
+            ctx.bumps.global_vault,
+            ctx.bumps.curve_vault,
+        )
+    }
+
+    //  view of the curve's current spot price, run it through a simulation
+    pub fn get_spot_price(ctx: Context<GetSpotPrice>) -> Result<u64> {
+        ctx.accounts.handler()
+    }
+
+    //  buys exactly the lamports needed to graduate and completes the curve in one go
+    pub fn complete_buy(ctx: Context<CompleteBuy>, max_lamports: u64) -> Result<u64> {
+        ctx.accounts.handler(max_lamports, ctx.bumps.global_vault, ctx.bumps.curve_vault)
+    }
+
+    //  view of the buy size that completes the curve, run it through a simulation
+    pub fn quote_to_graduate(ctx: Context<QuoteToGraduate>) -> Result<u64> {
+        ctx.accounts.handler()
+    }
+
+    //  view of a wallet's position and P/L on a curve, run it through a simulation
+    pub fn get_position(ctx: Context<GetPosition>) -> Result<i64> {
+        ctx.accounts.handler()
+    }
+
+    pub fn transfer_creator(ctx: Context<TransferCreator>, new_creator: Pubkey) -> Result<()> {
+        ctx.accounts.handler(new_creator)
+    }
+
+    //  moves a curve launched on the shared global vault onto its own sol vault
+    pub fn migrate_curve_vault(ctx: Context<MigrateCurveVault>) -> Result<()> {
+        ctx.accounts.handler(ctx.bumps.global_vault)
+    }
+
+    //  keeper completes a curve whose last buy crossed the graduation threshold
+    pub fn finalize(ctx: Context<Finalize>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
+    //  admin escape hatch for mistyped reserves, only before the first trade
+    pub fn admin_set_reserves(
+        ctx: Context<AdminSetReserves>,
+        virtual_sol_reserves: u64,
+        virtual_token_reserves: u64,
+    ) -> Result<()> {
+        ctx.accounts.handler(virtual_sol_reserves, virtual_token_reserves)
+    }
+
+    //  admin escape hatch for a curve stranded below its graduation target
+    pub fn force_complete(ctx: Context<ForceComplete>) -> Result<()> {
+        ctx.accounts.handler()
+    }
+
     //  backend receives a event when the curve is copmleted and run this instruction
     //  removes bonding curve and add liquidity to raydium
-    pub fn migrate(ctx: Context<Migrate>, nonce: u8) -> Result<()> {
-        ctx.accounts.process(nonce, ctx.bumps.global_vault)
+    pub fn migrate(ctx: Context<Migrate>, nonce: u8, burn_unsold: bool) -> Result<()> {
+        ctx.accounts.process(
+            nonce,
+            burn_unsold,
+            ctx.bumps.global_vault,
+            ctx.bumps.curve_vault,
+        )
+    }
+
+    //  releases the tokens set aside at launch for an early dex pool
+    pub fn seed_liquidity(ctx: Context<SeedLiquidity>) -> Result<()> {
+        ctx.accounts.process(ctx.bumps.bootstrap_liquidity)
+    }
+
+    //  dry run of `migrate`, emits the amounts it would deposit
+    pub fn simulate_migrate(ctx: Context<SimulateMigrate>, burn_unsold: bool) -> Result<()> {
+        ctx.accounts.process(burn_unsold)
+    }
+
+    //  burns the tokens left behind in the curve's token account after migration
+    pub fn sweep_dust(ctx: Context<SweepDust>) -> Result<()> {
+        ctx.accounts.process(ctx.bumps.global_vault)
     }
 }
 
@@ -0,0 +1,37 @@
+//! Canonical PDA derivations for off-chain clients and CPI callers
+
+use crate::constants::{BONDING_CURVE, CONFIG, CREATOR_STATS, CURVE_VAULT, GLOBAL, METADATA};
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::get_associated_token_address, metadata};
+
+pub fn config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG.as_bytes()], &crate::ID)
+}
+
+pub fn global_vault_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GLOBAL.as_bytes()], &crate::ID)
+}
+
+pub fn curve_vault_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CURVE_VAULT.as_bytes(), mint.as_ref()], &crate::ID)
+}
+
+pub fn bonding_curve_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BONDING_CURVE.as_bytes(), &mint.to_bytes()], &crate::ID)
+}
+
+pub fn creator_stats_pda(creator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CREATOR_STATS.as_bytes(), creator.as_ref()], &crate::ID)
+}
+
+pub fn metadata_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[METADATA.as_bytes(), metadata::ID.as_ref(), mint.as_ref()],
+        &metadata::ID,
+    )
+}
+
+//  token account of the global vault holding the curve's tokens
+pub fn global_token_account(mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(&global_vault_pda().0, mint)
+}
@@ -0,0 +1,7 @@
+use anchor_lang::prelude::*;
+
+//  a wallet is blocked from trading while its PDA exists
+#[account]
+pub struct BlockedWallet {
+    pub wallet: Pubkey,
+}
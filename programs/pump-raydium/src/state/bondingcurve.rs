@@ -1,16 +1,69 @@
+use crate::constants::SPOT_PRICE_SCALE;
 use crate::errors::*;
-use crate::events::CompleteEvent;
 use crate::state::config::*;
+use crate::state::cost_basis::CostBasis;
 use crate::utils::*;
 use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
-use anchor_spl::token::Mint;
 use anchor_spl::token::Token;
-use std::ops::Div;
-use std::ops::Mul;
-use std::ops::Sub;
 
 #[account]
+#[derive(Default)]
 pub struct BondingCurve {
+    //  kept first so indexers can memcmp on `BONDING_CURVE_IS_COMPLETED_OFFSET`
+    pub is_completed: bool,
+
+    pub token_mint: Pubkey,
+    pub creator: Pubkey,
+
+    /// Initial virtual SOL reserves the curve launched with (the `reserve_lamport` launch
+    /// argument). It seeds `virtual_sol_reserves` and so sets the opening price.
+    pub init_lamport: u64,
+
+    pub token_total_supply: u64,
+
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+
+    pub graduation_target: u64, //  real lamports needed to complete the curve
+    pub max_sol_reserves: u64,  //  hard cap on real lamports the curve accepts, 0 disables it
+
+    pub launch_slot: u64,
+    pub snipe_tax_slots: u64,
+    pub snipe_tax_bps: u16,
+
+    pub ready_to_complete: bool, //  set by the crossing buy, promoted to `is_completed` by `finalize`
+    pub is_migrated: bool,
+
+    pub completed_at: i64, //  unix timestamp of graduation, 0 while the curve is active
+
+    pub fee_override_bps: Option<u16>, //  set at launch, replaces the global buy and sell fees
+
+    //  pool deposit locked in when the curve completes, `migrate` deposits exactly these
+    pub migration_sol_amount: u64,
+    pub migration_token_amount: u64,
+
+    pub trade_count: u64, //  executed buys and sells, including the creator dev-buy
+
+    pub uses_curve_vault: bool, //  sol is held by the per-curve vault, shared-vault curves get it from `migrate_curve_vault`
+
+    pub creator_dev_buy_tokens: u64, //  tokens the creator bought at launch, base of `creator_min_hold_bps`
+
+    pub holder_count: u64, //  first buys through `swap` or `complete_buy` while `Config::tracks_positions`, buys made while untracked are never counted, see `Config::max_holders`
+
+    pub migration_target: u8, //  graduation dex chosen at launch, one of the `MIGRATION_TARGET_*` constants
+
+    pub team_fees_accrued: u64, //  unclaimed team fees held in the sol vault on top of the real sol reserves
+
+    pub ath_price_scaled: u64, //  highest post-trade spot price, scaled by 10^SPOT_PRICE_SCALE
+}
+
+//  layout of the curves launched on the shared global vault, before the per-curve vault
+//  `migrate_curve_vault` rewrites them in the current layout
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct LegacyBondingCurve {
     pub token_mint: Pubkey,
     pub creator: Pubkey,
 
@@ -27,172 +80,969 @@ pub struct BondingCurve {
     pub is_completed: bool,
 }
 
+impl LegacyBondingCurve {
+    //  a legacy curve that completed is handed to `finalize` as ready, so the completion is
+    //  counted and the pool deposit locked the same way as for any other curve
+    pub fn upgrade(self, global_config: &Config) -> BondingCurve {
+        let graduation_target = if self.is_completed {
+            global_config.curve_limit.min(self.real_sol_reserves)
+        } else {
+            global_config.curve_limit
+        };
+
+        let mut bonding_curve = BondingCurve {
+            token_mint: self.token_mint,
+            creator: self.creator,
+            init_lamport: self.init_lamport,
+            token_total_supply: self.token_total_supply,
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+            real_sol_reserves: self.real_sol_reserves,
+            real_token_reserves: self.real_token_reserves,
+            graduation_target,
+            ready_to_complete: self.is_completed,
+            uses_curve_vault: true,
+            ..Default::default()
+        };
+        bonding_curve.record_ath_price();
+
+        bonding_curve
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SellResult {
     pub token_amount: u64,
     pub sol_amount: u64,
 }
 
+#[derive(Debug, Clone)]
+pub struct TradeResult {
+    pub amount_out: u64, //  tokens on buys, lamports on sells
+    pub fees: FeeBreakdown,
+}
+
+#[derive(Debug, Clone)]
+pub struct SellSettlement {
+    pub token_amount: u64,
+    pub fee_amount: u64,
+    pub fee_bps: u16,          //  sell fee applied, after overrides
+    pub reflected_amount: u64, //  lamports left in the reserves for the remaining holders
+    pub seller_amount: u64,    //  net lamports paid to the seller
+}
+
 #[derive(Debug, Clone)]
 pub struct BuyResult {
     pub token_amount: u64,
     pub sol_amount: u64,
 }
 
+#[derive(Debug, Clone)]
+pub struct GraduationQuote {
+    pub buy_amount: u64,   //  lamports the buyer sends, fees and snipe tax included
+    pub net_lamports: u64, //  lamports the curve keeps once the last buy is partially filled
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationAmounts {
+    pub sol_amount: u64,     //  lamports deposited into the pool
+    pub token_amount: u64,   //  tokens deposited into the pool
+    pub fee_amount: u64,     //  migration fee sent to the team wallet
+    pub burn_amount: u64,    //  unsold tokens burned instead of deposited
+    pub surplus_amount: u64, //  lamports beyond the pool deposit returned to the team wallet
+}
+
+impl MigrationAmounts {
+    //  applies `Config::migration_surplus` to the lamports beyond `initial_raydium_sol_amount`
+    pub fn settle_surplus(self, global_config: &Config) -> Option<MigrationAmounts> {
+        let pool_sol_amount = global_config.initial_raydium_sol_amount;
+        if pool_sol_amount == 0 || self.sol_amount <= pool_sol_amount {
+            return Some(self);
+        }
+        let surplus = self.sol_amount - pool_sol_amount;
+
+        match global_config.migration_surplus {
+            MigrationSurplus::AddToPool => Some(self),
+            MigrationSurplus::ReturnToTeam => Some(MigrationAmounts {
+                sol_amount: pool_sol_amount,
+                surplus_amount: surplus,
+                ..self
+            }),
+            MigrationSurplus::Burn => {
+                //  the tokens the surplus would buy at the pool's opening price
+                let burned =
+                    (self.token_amount as u128 * surplus as u128 / self.sol_amount as u128) as u64;
+
+                Some(MigrationAmounts {
+                    token_amount: self.token_amount.checked_sub(burned)?,
+                    burn_amount: self.burn_amount.checked_add(burned)?,
+                    ..self
+                })
+            }
+        }
+    }
+}
+
+//  what `migrate` does with a completed curve, `simulate_migrate` previews the same plan
+#[derive(Debug, Clone)]
+pub struct MigrationPlan {
+    pub amounts: MigrationAmounts,
+    pub unsold_burn_amount: u64, //  part of `amounts.burn_amount` burned because of `burn_unsold`
+    pub lp_locked: u64,          //  lp burned out of the global vault's share, see `lp_to_lock`
+}
+
 pub trait BondingCurveAccount<'info> {
     // Updates the token reserves in the liquidity pool
 
     fn swap(
         &mut self,
-        global_config: &Account<'info, Config>,
-        token_mint: &Account<'info, Mint>,
+        global_config: &Config,
         global_ata: &mut AccountInfo<'info>,
         user_ata: &mut AccountInfo<'info>,
+        vault_authority: &AccountInfo<'info>,
         source: &mut AccountInfo<'info>,
         team_wallet: &mut AccountInfo<'info>,
         amount: u64,
         direction: u8,
         minimum_receive_amount: u64,
+        sell_fee_bps: Option<u16>, //  replaces the sell fee, see `loss_sell_fee_bps`
+        prepaid: u64,              //  lamports of a buy already moved into `source`, see `unwrap_user_wsol`
 
         user: &Signer<'info>,
         signer: &[&[&[u8]]],
 
         token_program: &Program<'info, Token>,
         system_program: &Program<'info, System>,
-    ) -> Result<u64>;
-
-    fn apply_sell(&mut self, token_amount: u64) -> Option<SellResult>;
-
-    fn apply_buy(&mut self, sol_amount: u64) -> Option<BuyResult>;
-
-    fn get_sol_for_sell_tokens(&self, token_amount: u64) -> Option<u64>;
-
-    fn get_tokens_for_buy_sol(&self, sol_amount: u64) -> Option<u64>;
+    ) -> Result<TradeResult>;
 }
 
 impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
     fn swap(
         &mut self,
-        global_config: &Account<'info, Config>,
+        global_config: &Config,
 
-        token_mint: &Account<'info, Mint>,
         global_ata: &mut AccountInfo<'info>,
         user_ata: &mut AccountInfo<'info>,
 
+        //  the global vault owns the curve tokens, `source` is the per-curve sol vault
+        //  `signer` carries the seeds of both
+        vault_authority: &AccountInfo<'info>,
         source: &mut AccountInfo<'info>,
         team_wallet: &mut AccountInfo<'info>,
 
         amount: u64,
         direction: u8,
         minimum_receive_amount: u64,
+        sell_fee_bps: Option<u16>,
+        prepaid: u64,
 
         user: &Signer<'info>,
         signer: &[&[&[u8]]],
 
         token_program: &Program<'info, Token>,
         system_program: &Program<'info, System>,
-    ) -> Result<u64> {
+    ) -> Result<TradeResult> {
         if amount <= 0 {
             return err!(ContractError::InvalidAmount);
         }
+        if direction == 1 {
+            require!(global_config.sells_enabled, ContractError::SellsDisabled);
+        } else {
+            require!(global_config.buys_enabled, ContractError::BuysDisabled);
+        }
+        if global_config.require_slippage_protection && minimum_receive_amount == 0 {
+            return err!(ContractError::SlippageProtectionRequired);
+        }
+
+        require!(prepaid == 0 || direction != 1, ContractError::InvalidAmount);
+        //  snapshot for the reserve reconciliation below, prepaid lamports aren't reserves yet
+        let vault_lamports_before = source
+            .lamports()
+            .checked_sub(prepaid)
+            .ok_or(ContractError::ArithmeticError)?;
+        let real_sol_reserves_before = self.real_sol_reserves;
+        let team_fees_accrued_before = self.team_fees_accrued;
+
+        let amount_out;
+        let fees;
+
+        if direction == 1 {
+            //Sell tokens
+            self.check_sell_near_graduation(global_config, amount)?;
+
+            let SellSettlement {
+                token_amount,
+                fee_amount,
+                fee_bps,
+                seller_amount,
+                ..
+            } = self.settle_sell_with_fee(
+                global_config,
+                amount,
+                minimum_receive_amount,
+                sell_fee_bps,
+            )?;
+
+            token_transfer_user(
+                user_ata.clone(),
+                &user,
+                global_ata.clone(),
+                &token_program,
+                token_amount,
+            )?;
+
+            sol_transfer_with_signer(
+                source.clone(),
+                user.to_account_info(),
+                &system_program,
+                signer,
+                seller_amount,
+            )?;
+
+            //  transfer fee to team wallet, or leave it in the vault for `claim_team_fees`
+            if global_config.accrues_team_fees() {
+                self.accrue_team_fees(fee_amount)?;
+            } else {
+                sol_transfer_with_signer(
+                    source.clone(),
+                    team_wallet.clone(),
+                    &system_program,
+                    signer,
+                    fee_amount,
+                )?;
+            }
+
+            amount_out = seller_amount;
+            fees = fee_breakdown(seller_amount, fee_amount, fee_bps)?;
+        } else
+        //buy tokens
+        {
+            let slot = Clock::get()?.slot;
+            let adjusted_amount = self.buy_amount_after_fees(global_config, amount, slot)?;
+
+            let max_buy_tokens =
+                calculate_fee(self.real_token_reserves, global_config.max_buy_fraction_bps)?;
+
+            let buy_result = self
+                .apply_buy(adjusted_amount)
+                .ok_or(ContractError::BuyFailed)?;
+            BondingCurve::check_min_out(buy_result.token_amount, minimum_receive_amount)?;
+
+            //  the completing buy is partially filled, the buyer pays the fees of the filled
+            //  part only and keeps the unfilled lamports together with their fee
+            let (charged_amount, fee_amount) = if buy_result.sol_amount < adjusted_amount {
+                let charged_amount = self
+                    .filled_buy_amount(global_config, amount, buy_result.sol_amount, slot)
+                    .ok_or(ContractError::BuyFailed)?;
+                (charged_amount, charged_amount - buy_result.sol_amount)
+            } else {
+                (amount, amount - adjusted_amount)
+            };
+
+            //  after `apply_buy` so the completing buy is known
+            self.check_min_buy(global_config, amount)?;
+            if self.ready_to_complete {
+                global_config.check_migration_backlog()?;
+                self.mark_completed_at(Clock::get()?.unix_timestamp);
+            }
+
+            if self.max_sol_reserves != 0 && self.real_sol_reserves > self.max_sol_reserves {
+                msg!("curve is capped at {} lamports", self.max_sol_reserves);
+                return err!(ContractError::CurveFull);
+            }
+
+            if global_config.max_buy_fraction_bps != 0 && buy_result.token_amount > max_buy_tokens {
+                msg!("buy of {} tokens exceeds the max of {}", buy_result.token_amount, max_buy_tokens);
+                return err!(ContractError::BuyTooLarge);
+            }
+
+            token_transfer_with_signer(
+                global_ata.clone(),
+                vault_authority.clone(),
+                user_ata.clone(),
+                &token_program,
+                signer,
+                buy_result.token_amount,
+            )?;
+
+            if prepaid > 0 {
+                //  the vault already holds the payment, it keeps the filled part, pays the fee
+                //  and snipe tax out of it and hands the rest back to the user as SOL
+                let refund = prepaid
+                    .checked_sub(buy_result.sol_amount)
+                    .and_then(|left| left.checked_sub(fee_amount))
+                    .ok_or(ContractError::InsufficientSol)?;
+                if global_config.accrues_team_fees() {
+                    self.accrue_team_fees(fee_amount)?;
+                } else {
+                    sol_transfer_with_signer(
+                        source.clone(),
+                        team_wallet.clone(),
+                        &system_program,
+                        signer,
+                        fee_amount,
+                    )?;
+                }
+                sol_transfer_with_signer(
+                    source.clone(),
+                    user.to_account_info(),
+                    &system_program,
+                    signer,
+                    refund,
+                )?;
+            } else {
+                sol_transfer_from_user(
+                    &user,
+                    source.clone(),
+                    &system_program,
+                    buy_result.sol_amount,
+                )?;
+
+                //  transfer fee and snipe tax to team wallet, or accrue them in the vault
+                if global_config.accrues_team_fees() {
+                    sol_transfer_from_user(&user, source.clone(), &system_program, fee_amount)?;
+                    self.accrue_team_fees(fee_amount)?;
+                } else {
+                    sol_transfer_from_user(&user, team_wallet.clone(), &system_program, fee_amount)?;
+                }
+            }
+            amount_out = buy_result.token_amount;
+            fees = fee_breakdown(
+                buy_result.sol_amount,
+                fee_amount,
+                self.buy_fee_bps(global_config, charged_amount),
+            )?;
+        }
+
+        //  accrued fees sit in the vault next to the reserves
+        let fees_accrued = self.team_fees_accrued - team_fees_accrued_before;
+        self.reconcile_reserves(
+            real_sol_reserves_before,
+            vault_lamports_before,
+            source.lamports() - fees_accrued,
+        )?;
+        self.trade_count += 1;
+        self.record_ath_price();
+
+        Ok(TradeResult { amount_out, fees })
+    }
+}
+
+/// Curve math rounds every intermediate result in favour of the pool: buys receive the
+/// floor of the computed tokens and sells receive the floor of the computed SOL, so the
+/// rounding remainder always stays in the reserves.
+impl BondingCurve {
+    //  `reserve_lamport` is the launch's initial virtual SOL reserve, the real reserves start empty
+    pub fn init_reserves(
+        &mut self,
+        reserve_lamport: u64,
+        virtual_token_reserves: u64,
+        real_token_reserves: u64,
+    ) {
+        self.init_lamport = reserve_lamport;
+        self.virtual_sol_reserves = reserve_lamport;
+        self.virtual_token_reserves = virtual_token_reserves;
+        self.real_sol_reserves = 0;
+        self.real_token_reserves = real_token_reserves;
+    }
+
+    //  lamports that reach the curve once the anti-snipe tax and platform fee are taken
+    pub fn buy_amount_after_fees(
+        &self,
+        global_config: &Config,
+        amount: u64,
+        slot: u64,
+    ) -> Result<u64> {
+        let snipe_tax = if self.snipe_tax_active(slot) {
+            calculate_fee(amount, self.snipe_tax_bps)?
+        } else {
+            0
+        };
+
+        let taxed_amount = amount - snipe_tax;
+        let fee_bps = self.buy_fee_bps(global_config, amount);
+
+        Ok(taxed_amount - calculate_fee(taxed_amount, fee_bps)?)
+    }
+
+    pub fn buy_fee_bps(&self, global_config: &Config, amount: u64) -> u16 {
+        self.fee_override_bps
+            .unwrap_or_else(|| global_config.buy_fee_bps(amount))
+    }
+
+    pub fn sell_fee_bps(&self, global_config: &Config, sol_amount: u64) -> u16 {
+        self.fee_override_bps
+            .unwrap_or_else(|| global_config.sell_fee_bps(sol_amount))
+    }
+
+    pub fn sell_fee(&self, global_config: &Config, sol_amount: u64) -> Result<u64> {
+        calculate_fee(sol_amount, self.sell_fee_bps(global_config, sol_amount))
+    }
+
+    //  applies a sell and its fee, slippage is checked on the net lamports the seller receives
+    //  so a fee can't push the payout below `minimum_receive_amount`
+    pub fn settle_sell(
+        &mut self,
+        global_config: &Config,
+        token_amount: u64,
+        minimum_receive_amount: u64,
+    ) -> Result<SellSettlement> {
+        self.settle_sell_with_fee(global_config, token_amount, minimum_receive_amount, None)
+    }
+
+    //  `settle_sell` with `fee_bps` replacing the curve's sell fee when set
+    pub fn settle_sell_with_fee(
+        &mut self,
+        global_config: &Config,
+        token_amount: u64,
+        minimum_receive_amount: u64,
+        fee_bps: Option<u16>,
+    ) -> Result<SellSettlement> {
+        let sell_result = self
+            .apply_sell(token_amount)
+            .ok_or(ContractError::SellFailed)?;
+        let fee_bps =
+            fee_bps.unwrap_or_else(|| self.sell_fee_bps(global_config, sell_result.sol_amount));
+        let fee_amount = calculate_fee(sell_result.sol_amount, fee_bps)?;
+        let reflected_amount =
+            calculate_fee(sell_result.sol_amount, global_config.sell_reflection_bps)?;
+
+        let seller_amount = self
+            .settle_sell_fee(
+                sell_result.sol_amount,
+                fee_amount,
+                global_config.sell_fee_from_reserves,
+            )
+            .and_then(|amount| self.reflect_sell(amount, reflected_amount))
+            .ok_or(ContractError::SellFailed)?;
+        BondingCurve::check_min_out(seller_amount, minimum_receive_amount)?;
+
+        Ok(SellSettlement {
+            token_amount: sell_result.token_amount,
+            fee_amount,
+            fee_bps,
+            reflected_amount,
+            seller_amount,
+        })
+    }
+
+    //  discounted sell fee for a wallet selling below its average entry, `None` when the grace
+    //  is off or the sell isn't at a loss. never above the fee the sell would pay otherwise
+    pub fn loss_sell_fee_bps(
+        &self,
+        global_config: &Config,
+        cost_basis: &CostBasis,
+        token_amount: u64,
+    ) -> Option<u16> {
+        if !global_config.loss_sell_grace {
+            return None;
+        }
+
+        let proceeds = self.get_sol_for_sell_tokens(token_amount)?;
+        if !cost_basis.is_loss(token_amount, proceeds) {
+            return None;
+        }
+
+        let fee_bps = self.sell_fee_bps(global_config, proceeds);
+        Some(global_config.loss_sell_fee_bps.min(fee_bps))
+    }
+
+    //  lamports a sell of `token_amount` would leave in the reserves, 0 when it can't settle
+    pub fn sell_reflection(&self, global_config: &Config, token_amount: u64) -> u64 {
+        self.clone()
+            .settle_sell(global_config, token_amount, 0)
+            .map_or(0, |settlement| settlement.reflected_amount)
+    }
+
+    //  output of a trade without executing it, `None` when the curve can't fill it
+    pub fn quote(&self, global_config: &Config, amount: u64, direction: u8, slot: u64) -> Option<u64> {
+        let mut curve = self.clone();
+
+        if direction == 1 {
+            let settlement = curve.settle_sell(global_config, amount, 0).ok()?;
+
+            Some(settlement.seller_amount)
+        } else {
+            let adjusted_amount = curve.buy_amount_after_fees(global_config, amount, slot).ok()?;
+
+            Some(curve.apply_buy(adjusted_amount)?.token_amount)
+        }
+    }
+
+    //  largest size up to `amount` that still gets the rate `minimum_receive_amount / amount`
+    //  the rate only worsens as the size grows, so a binary search finds it. 0 when nothing fills
+    pub fn clamp_to_slippage(
+        &self,
+        global_config: &Config,
+        amount: u64,
+        direction: u8,
+        minimum_receive_amount: u64,
+        slot: u64,
+    ) -> u64 {
+        let fills = |size: u64| match self.quote(global_config, size, direction, slot) {
+            Some(amount_out) => {
+                amount_out as u128 * amount as u128 >= minimum_receive_amount as u128 * size as u128
+            }
+            None => false,
+        };
+
+        if fills(amount) {
+            return amount;
+        }
+
+        let (mut low, mut high) = (0, amount);
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if fills(mid) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        low
+    }
+
+    //  smallest gross buy up to `amount` whose lamports after fees and snipe tax cover `sol_amount`,
+    //  what a partially filled buy is charged. `None` when even `amount` doesn't cover it
+    pub fn filled_buy_amount(
+        &self,
+        global_config: &Config,
+        amount: u64,
+        sol_amount: u64,
+        slot: u64,
+    ) -> Option<u64> {
+        let covers = |gross: u64| {
+            self.buy_amount_after_fees(global_config, gross, slot)
+                .is_ok_and(|net| net >= sol_amount)
+        };
+        if !covers(amount) {
+            return None;
+        }
+        if sol_amount == 0 {
+            return Some(0);
+        }
+
+        //  fees only ever take a share, so a gross below `sol_amount` never covers it
+        let (mut low, mut high) = (sol_amount - 1, amount);
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if covers(mid) {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+
+        Some(high)
+    }
+
+    //  gross lamports, fees and snipe tax included, a buy has to send to receive `token_amount`
+    //  reverts with `SlippageExceeded` when that is more than `max_input`
+    pub fn buy_amount_for_tokens(
+        &self,
+        global_config: &Config,
+        token_amount: u64,
+        max_input: u64,
+        slot: u64,
+    ) -> Result<u64> {
+        if token_amount == 0 {
+            return err!(ContractError::InvalidAmount);
+        }
+        let sol_amount = self
+            .get_sol_for_buy_tokens(token_amount)
+            .ok_or(ContractError::BuyFailed)?;
+
+        match self.filled_buy_amount(global_config, max_input, sol_amount, slot) {
+            Some(buy_amount) => Ok(buy_amount),
+            None => {
+                msg!("buying {token_amount} tokens costs more than {max_input} lamports");
+                err!(ContractError::SlippageExceeded)
+            }
+        }
+    }
+
+    //  smallest buy that completes the curve, either by reaching the graduation target or by
+    //  taking the last tokens. the per-buy caps are not applied, `None` once the curve is complete
+    pub fn quote_to_graduate(&self, global_config: &Config, slot: u64) -> Option<GraduationQuote> {
+        if self.is_completed || self.ready_to_complete {
+            return None;
+        }
+
+        let completes = |amount: u64| -> Option<u64> {
+            let adjusted_amount = self.buy_amount_after_fees(global_config, amount, slot).ok()?;
+            let mut curve = self.clone();
+            let buy_result = curve.apply_buy(adjusted_amount)?;
+
+            curve.ready_to_complete.then_some(buy_result.sol_amount)
+        };
+
+        //  grow the bound until a buy completes the curve, then search below it
+        let mut high = self
+            .graduation_target
+            .saturating_sub(self.real_sol_reserves)
+            .max(1);
+        while completes(high).is_none() {
+            high = high.checked_mul(2)?;
+        }
+        let mut low = 0;
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if completes(mid).is_some() {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+
+        Some(GraduationQuote {
+            buy_amount: high,
+            net_lamports: completes(high)?,
+        })
+    }
+
+    pub fn check_min_out(amount_out: u64, minimum_receive_amount: u64) -> Result<()> {
+        if amount_out < minimum_receive_amount {
+            msg!("received {amount_out}, expected at least {minimum_receive_amount}");
+            return err!(ContractError::SlippageExceeded);
+        }
+        Ok(())
+    }
+
+    //  virtual_sol_reserves / virtual_token_reserves * 10^scale, early prices are a tiny fraction
+    //  of a lamport per base unit and would round to zero unscaled. saturates instead of overflowing
+    pub fn spot_price_lamports_per_token_scaled(&self, scale: u32) -> u64 {
+        if self.virtual_token_reserves == 0 {
+            return 0;
+        }
+
+        10u128
+            .checked_pow(scale)
+            .and_then(|multiplier| (self.virtual_sol_reserves as u128).checked_mul(multiplier))
+            .map(|value| value / self.virtual_token_reserves as u128)
+            .and_then(|value| u64::try_from(value).ok())
+            .unwrap_or(u64::MAX)
+    }
+
+    //  raises `ath_price_scaled` to the current spot price, true when it set a new high
+    pub fn record_ath_price(&mut self) -> bool {
+        let spot_price = self.spot_price_lamports_per_token_scaled(SPOT_PRICE_SCALE);
+        if spot_price <= self.ath_price_scaled {
+            return false;
+        }
+        self.ath_price_scaled = spot_price;
+
+        true
+    }
+
+    //  a trade must move the vault lamports by exactly the change in the real sol reserves,
+    //  and the vault must still hold at least those reserves
+    pub fn reconcile_reserves(
+        &self,
+        real_sol_reserves_before: u64,
+        vault_lamports_before: u64,
+        vault_lamports_after: u64,
+    ) -> Result<()> {
+        let reserves_delta = self.real_sol_reserves as i128 - real_sol_reserves_before as i128;
+        let vault_delta = vault_lamports_after as i128 - vault_lamports_before as i128;
+        if reserves_delta != vault_delta || self.real_sol_reserves > vault_lamports_after {
+            msg!(
+                "real sol reserves moved by {reserves_delta} to {}, vault moved by {vault_delta} to {vault_lamports_after}",
+                self.real_sol_reserves
+            );
+            return Err(ReserveMismatch.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn accrue_team_fees(&mut self, fee_amount: u64) -> Result<()> {
+        self.team_fees_accrued = self
+            .team_fees_accrued
+            .checked_add(fee_amount)
+            .ok_or(ContractError::ArithmeticError)?;
+
+        Ok(())
+    }
+
+    //  keeps the first graduation time, later calls are no-ops
+    pub fn mark_completed_at(&mut self, timestamp: i64) {
+        if self.completed_at == 0 {
+            self.completed_at = timestamp;
+        }
+    }
+
+    //  keeps a bot from migrating in the same block the curve graduated in
+    pub fn check_migration_delay(&self, global_config: &Config, now: i64) -> Result<()> {
+        let delay = i64::try_from(global_config.migration_delay_seconds).unwrap_or(i64::MAX);
+        let migrate_at = self.completed_at.saturating_add(delay);
+        if now < migrate_at {
+            msg!("curve graduated at {}, migration opens at {migrate_at}", self.completed_at);
+            return Err(MigrationTooEarly.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn snipe_tax_active(&self, slot: u64) -> bool {
+        self.snipe_tax_bps != 0 && slot < self.launch_slot.saturating_add(self.snipe_tax_slots)
+    }
+
+    //  pre-graduation sells by the creator have to leave `creator_min_hold_bps` of the dev-buy
+    //  in `balance`, the seller's token balance before the sell
+    pub fn check_creator_hold(
+        &self,
+        global_config: &Config,
+        seller: &Pubkey,
+        balance: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        if global_config.creator_min_hold_bps == 0 || *seller != self.creator || self.is_completed {
+            return Ok(());
+        }
+
+        let min_hold = calculate_fee(self.creator_dev_buy_tokens, global_config.creator_min_hold_bps)?;
+        let remaining = balance.saturating_sub(token_amount);
+        if remaining < min_hold {
+            msg!("creator would keep {remaining} tokens, at least {min_hold} have to be held until graduation");
+            return Err(CreatorSellLimited.into());
+        }
+
+        Ok(())
+    }
+
+    //  keeps churn from stranding a curve just below graduation: inside the graduation window a
+    //  single sell can take at most `sell_limit_near_graduation_bps` of the real sol reserves
+    pub fn check_sell_near_graduation(&self, global_config: &Config, token_amount: u64) -> Result<()> {
+        let limit_bps = global_config.sell_limit_near_graduation_bps;
+        if limit_bps == 0 || self.progress_bps() < global_config.graduation_window_bps {
+            return Ok(());
+        }
+
+        let sol_amount = self
+            .get_sol_for_sell_tokens(token_amount)
+            .ok_or(ContractError::SellFailed)?;
+        let max_sol_amount = calculate_fee(self.real_sol_reserves, limit_bps)?;
+        if sol_amount > max_sol_amount {
+            msg!(
+                "sell of {sol_amount} lamports exceeds the max of {max_sol_amount} at {} bps progress",
+                self.progress_bps()
+            );
+            return Err(SellLimitedNearGraduation.into());
+        }
+
+        Ok(())
+    }
+
+    //  rejects dust buys inching the curve toward graduation. the buy that completes the curve
+    //  only needs what's left to graduate, which can be less than the floor, so it's exempt
+    pub fn check_min_buy(&self, global_config: &Config, amount: u64) -> Result<()> {
+        if self.ready_to_complete || amount >= global_config.min_buy_lamports {
+            return Ok(());
+        }
+
+        msg!("buy of {amount} lamports is below the min of {}", global_config.min_buy_lamports);
+        Err(BuyTooSmall.into())
+    }
+
+    //  counts a first-time buyer, existing holders are never held back by `max_holders`
+    //  cost bases are only stamped while positions are tracked, so nothing is counted otherwise
+    pub fn record_new_holder(&mut self, global_config: &Config) -> Result<()> {
+        if !global_config.tracks_positions() {
+            return Ok(());
+        }
+        if global_config.max_holders != 0 && self.holder_count >= global_config.max_holders {
+            msg!("curve has {} holders, the max is {}", self.holder_count, global_config.max_holders);
+            return Err(MaxHoldersReached.into());
+        }
+        self.holder_count += 1;
+
+        Ok(())
+    }
+
+    //  position checks ahead of a `swap` or `complete_buy` trade while positions are tracked,
+    //  the trade needs the wallet's cost basis and a first buy counts a new holder
+    pub fn open_position(
+        &mut self,
+        global_config: &Config,
+        cost_basis: Option<&CostBasis>,
+        direction: u8,
+    ) -> Result<()> {
+        if !global_config.tracks_positions() {
+            return Ok(());
+        }
+        let cost_basis = cost_basis.ok_or(CostBasisRequired)?;
+        if direction != 1 && cost_basis.is_new() {
+            self.record_new_holder(global_config)?;
+        }
+
+        Ok(())
+    }
+
+    //  the launch reserve checks of `TokenLaunchParams::validate`, run against the curve's own
+    //  real reserves and target before `admin_set_reserves` replaces the virtual ones
+    pub fn validate_reserves(
+        &self,
+        virtual_sol_reserves: u64,
+        virtual_token_reserves: u64,
+    ) -> Result<()> {
+        if virtual_token_reserves < self.real_token_reserves {
+            msg!(
+                "virtual_token_reserves: {virtual_token_reserves} is below the real token reserves {}",
+                self.real_token_reserves
+            );
+            return Err(ValueInvalid.into());
+        }
+
+        let max_raise =
+            max_curve_raise(virtual_sol_reserves, virtual_token_reserves, self.real_token_reserves);
+        if max_raise < self.graduation_target {
+            msg!(
+                "virtual_sol_reserves: {virtual_sol_reserves} raises at most {max_raise} lamports, below the graduation target {}",
+                self.graduation_target
+            );
+            return Err(ValueInvalid.into());
+        }
+
+        Ok(())
+    }
+
+    //  real lamports raised as a share of the graduation target, capped at 10000
+    pub fn progress_bps(&self) -> u16 {
+        if self.graduation_target == 0 {
+            return 0;
+        }
+        let progress = self.real_sol_reserves as u128 * 10_000 / self.graduation_target as u128;
+
+        progress.min(10_000) as u16
+    }
+
+    //  amounts the migration deposits, `token_balance` is what the global token account holds
+    //  with `burn_unsold` only the configured pool reserve is deposited and the rest is burned
+    pub fn migration_amounts(
+        &self,
+        global_config: &Config,
+        token_balance: u64,
+        burn_unsold: bool,
+    ) -> Result<MigrationAmounts> {
+        let fee_amount = calculate_fee(
+            self.real_sol_reserves,
+            percent_to_bps(global_config.platform_migration_fee),
+        )?;
+        let sol_amount = self.real_sol_reserves - fee_amount;
+
+        let token_amount = if burn_unsold {
+            token_balance.min(global_config.initial_raydium_token_reserves)
+        } else {
+            token_balance
+        };
+
+        Ok(MigrationAmounts {
+            sol_amount,
+            token_amount,
+            fee_amount,
+            burn_amount: token_balance - token_amount,
+            surplus_amount: 0,
+        })
+    }
+
+    //  locks in the pool deposit at completion so later config changes can't make it drift
+    pub fn lock_migration_amounts(
+        &mut self,
+        global_config: &Config,
+        token_balance: u64,
+    ) -> Result<()> {
+        let amounts = self.migration_amounts(global_config, token_balance, false)?;
+        self.migration_sol_amount = amounts.sol_amount;
+        self.migration_token_amount = amounts.token_amount;
+
+        Ok(())
+    }
+
+    //  the amounts locked at completion, `burn_unsold` works like in `migration_amounts`
+    pub fn locked_migration_amounts(
+        &self,
+        global_config: &Config,
+        burn_unsold: bool,
+    ) -> MigrationAmounts {
+        let token_amount = if burn_unsold {
+            self.migration_token_amount
+                .min(global_config.initial_raydium_token_reserves)
+        } else {
+            self.migration_token_amount
+        };
+
+        MigrationAmounts {
+            sol_amount: self.migration_sol_amount,
+            token_amount,
+            fee_amount: self.real_sol_reserves.saturating_sub(self.migration_sol_amount),
+            burn_amount: self.migration_token_amount - token_amount,
+            surplus_amount: 0,
+        }
+    }
 
-        let amount_out = source.lamports();
+    //  the locked amounts with the surplus settled and the lp lock sized, `lp_decimals` are the
+    //  coin mint's decimals, which raydium gives the lp mint
+    pub fn migration_plan(
+        &self,
+        global_config: &Config,
+        burn_unsold: bool,
+        lp_decimals: u8,
+    ) -> Result<MigrationPlan> {
+        let mut amounts = self.locked_migration_amounts(global_config, burn_unsold);
+        let unsold_burn_amount = amounts.burn_amount;
+        //  curves on the shared vault had their sol wrapped before, they deposit all of it
+        if self.uses_curve_vault {
+            amounts = amounts
+                .settle_surplus(global_config)
+                .ok_or(ArithmeticError)?;
+        }
 
-        msg!("amount_out {:?}", amount_out);
-        sol_transfer_with_signer(
-            source.clone(),
-            user.to_account_info(),
-            &system_program,
-            signer,
-            amount_out,
+        let lp_locked = lp_to_lock(
+            global_config.locked_lp_amount,
+            amounts.token_amount,
+            amounts.sol_amount,
+            lp_decimals,
         )?;
 
-        // if direction == 1 {
-        //     //Sell tokens
-        //     let sell_result = self.apply_sell(amount).ok_or(ContractError::SellFailed)?;
-
-        //     token_transfer_user(
-        //         user_ata.clone(),
-        //         &user,
-        //         global_ata.clone(),
-        //         &token_program,
-        //         sell_result.token_amount,
-        //     )?;
-
-        //     let adjusted_amount_in_float = convert_to_float(sell_result.sol_amount, 9)
-        //         .div(100_f64)
-        //         .mul(100_f64.sub(global_config.platform_sell_fee));
-
-        //     let adjusted_amount = convert_from_float(adjusted_amount_in_float, 9);
-
-        //     sol_transfer_with_signer(
-        //         source.clone(),
-        //         user.to_account_info(),
-        //         &system_program,
-        //         signer,
-        //         adjusted_amount,
-        //     )?;
-
-        //     //  transfer fee to team wallet
-        //     let fee_amount = sell_result.sol_amount - adjusted_amount;
-
-        //     sol_transfer_with_signer(
-        //         source.clone(),
-        //         team_wallet.clone(),
-        //         &system_program,
-        //         signer,
-        //         fee_amount,
-        //     )?;
-
-        //     amount_out = sell_result.token_amount;
-        // } else
-        // //buy tokens
-        // {
-        //     let adjusted_amount_in_float = convert_to_float(amount, 9)
-        //         .div(100_f64)
-        //         .mul(100_f64.sub(global_config.platform_sell_fee));
-
-        //     let adjusted_amount = convert_from_float(adjusted_amount_in_float, 9);
-
-        //     let buy_result = self
-        //         .apply_buy(adjusted_amount)
-        //         .ok_or(ContractError::BuyFailed)?;
-
-        //     if self.is_completed == true {
-        //         emit!(CompleteEvent {
-        //             user: user.key(),
-        //             mint: token_mint.key(),
-        //             bonding_curve: self.key()
-        //         });
-        //     }
-
-        //     token_transfer_with_signer(
-        //         global_ata.clone(),
-        //         source.clone(),
-        //         user_ata.clone(),
-        //         &token_program,
-        //         signer,
-        //         buy_result.token_amount,
-        //     )?;
-
-        //     sol_transfer_from_user(
-        //         &user,
-        //         source.clone(),
-        //         &system_program,
-        //         buy_result.sol_amount,
-        //     )?;
-
-        //     //  transfer fee to team wallet
-        //     let fee_amount = amount - adjusted_amount;
-
-        //     sol_transfer_from_user(&user, team_wallet.clone(), &system_program, fee_amount)?;
-        //     amount_out = buy_result.sol_amount;
-        // }
-
-        Ok(amount_out)
-    }
-
-    fn get_sol_for_sell_tokens(&self, token_amount: u64) -> Option<u64> {
+        Ok(MigrationPlan {
+            amounts,
+            unsold_burn_amount,
+            lp_locked,
+        })
+    }
+
+    //  settles the sell fee and returns the lamports paid to the seller
+    //  from proceeds: the fee is deducted from the seller's output, reserves are unaffected
+    //  from reserves: the seller keeps the full curve output and the fee is deducted from the
+    //  sol reserves on top, lowering the price for the remaining holders
+    pub fn settle_sell_fee(
+        &mut self,
+        sol_amount: u64,
+        fee_amount: u64,
+        from_reserves: bool,
+    ) -> Option<u64> {
+        if from_reserves {
+            self.virtual_sol_reserves = self.virtual_sol_reserves.checked_sub(fee_amount)?;
+            self.real_sol_reserves = self.real_sol_reserves.checked_sub(fee_amount)?;
+
+            Some(sol_amount)
+        } else {
+            sol_amount.checked_sub(fee_amount)
+        }
+    }
+
+    //  keeps `reflected_amount` of the seller's lamports in the vault and credits it back to the
+    //  sol reserves, which raises the price for the remaining holders. returns the seller's rest
+    pub fn reflect_sell(&mut self, seller_amount: u64, reflected_amount: u64) -> Option<u64> {
+        let seller_amount = seller_amount.checked_sub(reflected_amount)?;
+        self.virtual_sol_reserves = self.virtual_sol_reserves.checked_add(reflected_amount)?;
+        self.real_sol_reserves = self.real_sol_reserves.checked_add(reflected_amount)?;
+
+        Some(seller_amount)
+    }
+
+    pub fn get_sol_for_sell_tokens(&self, token_amount: u64) -> Option<u64> {
         if token_amount == 0 {
             return None;
         }
@@ -210,14 +1060,17 @@ impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
                 .checked_div(1_000_000)?, // From 6 decimals
         )?;
 
-        let new_sol = (current_sol.checked_mul(current_tokens)?).checked_div(new_tokens)?;
+        // Round the remaining reserve up so the seller never receives the rounding remainder
+        let new_sol = (current_sol.checked_mul(current_tokens)?)
+            .checked_add(new_tokens.checked_sub(1)?)?
+            .checked_div(new_tokens)?;
 
         let sol_out = current_sol.checked_sub(new_sol)?;
 
         <u128 as TryInto<u64>>::try_into(sol_out).ok()
     }
 
-    fn get_tokens_for_buy_sol(&self, sol_amount: u64) -> Option<u64> {
+    pub fn get_tokens_for_buy_sol(&self, sol_amount: u64) -> Option<u64> {
         if sol_amount == 0 {
             return None;
         }
@@ -230,7 +1083,10 @@ impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
 
         // Calculate new reserves using constant product formula
         let new_sol = current_sol.checked_add(sol_amount as u128)?;
-        let new_tokens = (current_sol.checked_mul(current_tokens)?).checked_div(new_sol)?;
+        // Round the remaining reserve up so the buyer never receives the rounding remainder
+        let new_tokens = (current_sol.checked_mul(current_tokens)?)
+            .checked_add(new_sol.checked_sub(1)?)?
+            .checked_div(new_sol)?;
 
         let tokens_out = current_tokens.checked_sub(new_tokens)?;
 
@@ -242,7 +1098,39 @@ impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
         <u128 as TryInto<u64>>::try_into(tokens_out).ok()
     }
 
-    fn apply_buy(&mut self, mut sol_amount: u64) -> Option<BuyResult> {
+    //  inverse of `get_tokens_for_buy_sol`: the smallest sol amount that buys at least
+    //  `token_amount`. `None` when the curve doesn't hold that many tokens
+    pub fn get_sol_for_buy_tokens(&self, token_amount: u64) -> Option<u64> {
+        if token_amount == 0 || token_amount > self.real_token_reserves {
+            return None;
+        }
+
+        // Convert to common decimal basis (using 9 decimals as base)
+        let current_sol = self.virtual_sol_reserves as u128;
+        let current_tokens = (self.virtual_token_reserves as u128)
+            .checked_mul(1_000_000_000)? // Scale tokens up to 9 decimals
+            .checked_div(1_000_000)?; // From 6 decimals
+        let tokens_out = (token_amount as u128)
+            .checked_mul(1_000_000_000)? // Scale output tokens to 9 decimals
+            .checked_div(1_000_000)?; // From 6 decimals
+
+        // Largest token reserve the buy may leave behind
+        let new_tokens = current_tokens.checked_sub(tokens_out)?;
+        if new_tokens == 0 {
+            return None;
+        }
+
+        // Round the new sol reserve up, the buy rounds the remaining token reserve up as well
+        let new_sol = (current_sol.checked_mul(current_tokens)?)
+            .checked_add(new_tokens.checked_sub(1)?)?
+            .checked_div(new_tokens)?;
+
+        let sol_in = new_sol.checked_sub(current_sol)?;
+
+        <u128 as TryInto<u64>>::try_into(sol_in).ok()
+    }
+
+    pub fn apply_buy(&mut self, mut sol_amount: u64) -> Option<BuyResult> {
         // Computing Token Amount out
         let mut token_amount = self.get_tokens_for_buy_sol(sol_amount)?;
 
@@ -250,29 +1138,11 @@ impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
             // Last Buy
             token_amount = self.real_token_reserves;
 
-            // Temporarily store the current state
-            let current_virtual_token_reserves = self.virtual_token_reserves;
-            let current_virtual_sol_reserves = self.virtual_sol_reserves;
-
-            // Update self with the new token amount
-            self.virtual_token_reserves = (current_virtual_token_reserves as u128)
-                .checked_sub(token_amount as u128)?
-                .try_into()
-                .ok()?;
-            msg!("virtual_token_reserves: {:?}", self.virtual_token_reserves);
-            self.virtual_sol_reserves = 1_916_755_017; //115_005_301_050; //85_005_359_056; // Total raise amount at end
-
-            let recomputed_sol_amount = self.get_sol_for_sell_tokens(token_amount)?;
-            msg!("recomputed_sol_amount: {:?}", recomputed_sol_amount);
+            // Priced from the live reserves, the buyer only pays for the tokens that are left
+            sol_amount = self.get_sol_for_buy_tokens(token_amount)?;
 
-            sol_amount = recomputed_sol_amount;
-
-            // Restore the state with the recomputed sol_amount
-            self.virtual_token_reserves = current_virtual_token_reserves;
-            self.virtual_sol_reserves = current_virtual_sol_reserves;
-
-            // Set complete to true
-            self.is_completed = true;
+            // Ready for a keeper to finalize
+            self.ready_to_complete = true;
         }
 
         // Adjusting token reserve values
@@ -298,13 +1168,17 @@ impl<'info> BondingCurveAccount<'info> for Account<'info, BondingCurve> {
         self.virtual_sol_reserves = new_virtual_sol_reserves.try_into().ok()?;
         self.real_sol_reserves = new_real_sol_reserves.try_into().ok()?;
 
+        if self.graduation_target != 0 && self.real_sol_reserves >= self.graduation_target {
+            self.ready_to_complete = true;
+        }
+
         Some(BuyResult {
             token_amount,
             sol_amount,
         })
     }
 
-    fn apply_sell(&mut self, token_amount: u64) -> Option<SellResult> {
+    pub fn apply_sell(&mut self, token_amount: u64) -> Option<SellResult> {
         // Computing Sol Amount out
         let sol_amount = self.get_sol_for_sell_tokens(token_amount)?;
 
@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+//  tokens set aside at launch for early dex liquidity, held outside the curve reserves
+#[account]
+pub struct BootstrapLiquidity {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub seeded: bool, //  set once `seed_liquidity` has moved the tokens out
+}
@@ -1,11 +1,18 @@
+use crate::constants::FEE_MODE_ACCRUE;
 use crate::errors::*;
-use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
+use crate::utils::{self, percent_to_bps};
+use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize, Discriminator};
 use core::fmt::Debug;
 
+pub const CONFIG_VERSION: u8 = 1;
+pub const LEGACY_CONFIG_VERSION: u8 = 0; //  configs stored before the `version` field, see `LegacyConfig`
+
 #[account]
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Config {
+    //  `authority` stays first in every layout, `stored_authority` reads it at a fixed offset
     pub authority: Pubkey,
+    pub version: u8, //  layout version, always stamped by `configure`
     //  use this for meteora migration
     pub migration_authority: Pubkey,
 
@@ -21,18 +28,452 @@ pub struct Config {
     pub curve_limit: u64, //  lamports to complete te bonding curve
 
     pub lamport_amount_config: AmountConfig<u64>,
+    pub graduation_target_config: AmountConfig<u64>, //  allowed range for per-launch graduation targets
     pub token_supply_config: AmountConfig<u64>,
     pub token_decimals_config: AmountConfig<u8>,
 
     pub initial_virtual_token_reserves_config: u64, // vtr
-    pub initial_virtual_sol_reserves_config: u64,   // vsr
+    pub initial_virtual_sol_reserves_config: u64,   // vsr, default `reserve_lamport` for clients, see `max_curve_raise`
     pub initial_real_token_reserves_config: u64,    // e.g 80% reserved for the bodning curve
     pub initial_raydium_token_reserves: u64,        // e.g 20% of tokens for the raydium pool
     pub initial_raydium_sol_amount: u64,            // the sol amount to be seeded inside the pool
 
+    pub snipe_tax_slots: u64, //  slots after launch during which buys pay the snipe tax
+    pub snipe_tax_bps: u16,   //  extra buy fee in basis points, sent to the team wallet
+
+    pub fee_tiers: Vec<FeeTier>, //  sorted by `min_lamports`, overrides the flat platform fees when non-empty
+
+    pub emit_reserves: bool, //  emit a `ReserveSnapshot` after every trade for charting
+
+    pub min_initial_buy_lamports: u64, //  minimum creator dev-buy at launch, 0 keeps the dev-buy optional
+
+    pub min_slots_between_launches: u64, //  per-creator launch throttle, 0 disables it
+
+    pub max_buy_fraction_bps: u16, //  max share of the real token reserves a single buy can take, 0 disables it
+
+    pub sell_fee_from_reserves: bool, //  take the sell fee from the curve reserves instead of the seller's proceeds
+
+    pub max_sol_reserves: u64, //  hard cap on real lamports per curve, 0 disables it
+
+    pub enforce_blocklist: bool, //  reject trades from wallets with a `BlockedWallet` PDA
+
+    pub require_slippage_protection: bool, //  reject trades with a zero minimum output
+
+    pub fee_override_config: AmountConfig<u16>, //  allowed per-curve fee overrides in basis points
+
+    pub buys_enabled: bool,  //  toggled by `set_trading` for incident response
+    pub sells_enabled: bool,
+
+    pub max_bootstrap_liquidity_bps: u16, //  max share of the supply a launch can set aside for early liquidity
+
+    pub creator_min_hold_bps: u16, //  share of the dev-buy the creator keeps until graduation, 0 disables it
+
+    pub allowed_uri_prefixes: Vec<String>, //  e.g `https://`, `ipfs://`, empty accepts any metadata uri
+
+    pub locked_lp_amount: u64, //  LP tokens burned at migration so the pool can never be fully drained, 0 disables it
+
+    pub sell_reflection_bps: u16, //  share of sell proceeds left in the curve reserves for the remaining holders, 0 disables it
+
+    //  backpressure for the migration keepers: once `max_pending_migrations` curves are completed
+    //  but not migrated, buys that would graduate another curve fail until a migration lands
+    pub max_pending_migrations: u64, //  0 disables the cap
+    pub pending_migrations: u64,     //  maintained by the program, `configure` keeps the stored value
+
+    pub active_curves: u64, //  launched curves that haven't completed yet, maintained like `pending_migrations`
+
+    pub loss_sell_grace: bool,  //  track per-wallet cost basis and discount sells below the average entry
+    pub loss_sell_fee_bps: u16, //  sell fee for those exits, never above the regular fee
+
+    pub graduation_window_bps: u16, //  curve progress from which `sell_limit_near_graduation_bps` applies
+    pub sell_limit_near_graduation_bps: u16, //  max share of the real sol reserves one sell can take in that window, 0 disables it
+
+    //  fair-distribution cap on wallets per curve. a holder is a wallet whose cost basis pda was
+    //  created by a `swap` buy, so it counts addresses that ever bought, not current holders
+    pub max_holders: u64, //  0 disables it
+
+    pub migration_surplus: MigrationSurplus, //  what `migrate` does with lamports beyond `initial_raydium_sol_amount`
+
+    pub min_buy_lamports: u64, //  smallest gross buy, the buy that graduates the curve is exempt. 0 disables it
+
+    pub migration_delay_seconds: u64, //  cooldown between graduation and migration, 0 migrates right away
+
+    pub fee_mode: u8, //  `FEE_MODE_TRANSFER` or `FEE_MODE_ACCRUE`
+
+    //  ceiling on every trading fee, including per-curve overrides. once set it can only be
+    //  lowered by `configure`, never raised or cleared
+    pub max_fee_basis_points: u16, //  0 until set
+
+    pub require_verified_creator: bool, //  launches always list and verify the creator in the metadata
+
+    pub initialized: bool, //  always set by `configure`, launches refuse a config without it
+}
+
+//  a curve can raise more than the pool is configured to open with, e.g when the last buy
+//  overshoots the graduation target. `initial_raydium_sol_amount` of 0 deposits everything
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MigrationSurplus {
+    AddToPool,    //  deposit the surplus, the pool opens at a higher price
+    ReturnToTeam, //  send the surplus to the team wallet along with the migration fee
+    Burn,         //  deposit it and burn the tokens it would buy at the opening price
+}
+
+//  layout of the configs stored before `version` was added, new fields default when upgrading
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct LegacyConfig {
+    pub authority: Pubkey,
+    pub migration_authority: Pubkey,
+
+    pub team_wallet: Pubkey,
+    pub migration_wallet: Pubkey,
+
+    pub init_bonding_curve: f64,
+
+    pub platform_buy_fee: f64,
+    pub platform_sell_fee: f64,
+    pub platform_migration_fee: f64,
+
+    pub curve_limit: u64,
+
+    pub lamport_amount_config: AmountConfig<u64>,
+    pub token_supply_config: AmountConfig<u64>,
+    pub token_decimals_config: AmountConfig<u8>,
+
+    pub initial_virtual_token_reserves_config: u64,
+    pub initial_virtual_sol_reserves_config: u64,
+    pub initial_real_token_reserves_config: u64,
+    pub initial_raydium_token_reserves: u64,
+    pub initial_raydium_sol_amount: u64,
+
     pub initialized: bool,
 }
 
+impl LegacyConfig {
+    //  new fields start disabled, launches can't pick their own graduation target or fee
+    //  override until `configure` sets the allowed ranges
+    pub fn upgrade(self) -> Config {
+        Config {
+            authority: self.authority,
+            version: CONFIG_VERSION,
+            migration_authority: self.migration_authority,
+            team_wallet: self.team_wallet,
+            migration_wallet: self.migration_wallet,
+            init_bonding_curve: self.init_bonding_curve,
+            platform_buy_fee: self.platform_buy_fee,
+            platform_sell_fee: self.platform_sell_fee,
+            platform_migration_fee: self.platform_migration_fee,
+            curve_limit: self.curve_limit,
+            lamport_amount_config: self.lamport_amount_config,
+            graduation_target_config: AmountConfig::Enum(vec![self.curve_limit]),
+            token_supply_config: self.token_supply_config,
+            token_decimals_config: self.token_decimals_config,
+            initial_virtual_token_reserves_config: self.initial_virtual_token_reserves_config,
+            initial_virtual_sol_reserves_config: self.initial_virtual_sol_reserves_config,
+            initial_real_token_reserves_config: self.initial_real_token_reserves_config,
+            initial_raydium_token_reserves: self.initial_raydium_token_reserves,
+            initial_raydium_sol_amount: self.initial_raydium_sol_amount,
+            snipe_tax_slots: 0,
+            snipe_tax_bps: 0,
+            fee_tiers: vec![],
+            emit_reserves: false,
+            min_initial_buy_lamports: 0,
+            min_slots_between_launches: 0,
+            max_buy_fraction_bps: 0,
+            sell_fee_from_reserves: false,
+            max_sol_reserves: 0,
+            enforce_blocklist: false,
+            require_slippage_protection: false,
+            fee_override_config: AmountConfig::Enum(vec![]),
+            buys_enabled: true,
+            sells_enabled: true,
+            max_bootstrap_liquidity_bps: 0,
+            creator_min_hold_bps: 0,
+            allowed_uri_prefixes: vec![],
+            locked_lp_amount: 0,
+            sell_reflection_bps: 0,
+            max_pending_migrations: 0,
+            pending_migrations: 0,
+            active_curves: 0,
+            loss_sell_grace: false,
+            loss_sell_fee_bps: 0,
+            graduation_window_bps: 10_000,
+            sell_limit_near_graduation_bps: 0,
+            max_holders: 0,
+            migration_surplus: MigrationSurplus::AddToPool,
+            min_buy_lamports: 0,
+            migration_delay_seconds: 0,
+            fee_mode: 0,
+            max_fee_basis_points: 0,
+            require_verified_creator: false,
+            initialized: self.initialized,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct FeeTier {
+    pub min_lamports: u64, //  smallest trade size this tier applies to
+    pub fee_bps: u16,
+}
+
+impl Config {
+    //  reads the version and authority of a stored config of any layout, so older configs can
+    //  always be upgraded. `authority` is right after the discriminator in every layout, the
+    //  version is detected by decoding: the current layout first, then the legacy one
+    pub fn stored_authority(data: &[u8]) -> Result<(u8, Pubkey)> {
+        if data.len() < 8 + 32 || &data[0..8] != Config::DISCRIMINATOR {
+            return Err(IncorrectConfigAccount.into());
+        }
+        let authority = Pubkey::try_from(&data[8..40]).map_err(|_| IncorrectConfigAccount)?;
+
+        let version = if Config::deserialize(&mut &data[8..])
+            .is_ok_and(|config| config.version == CONFIG_VERSION)
+        {
+            CONFIG_VERSION
+        } else if LegacyConfig::deserialize(&mut &data[8..]).is_ok() {
+            LEGACY_CONFIG_VERSION
+        } else {
+            msg!("config layout is not recognized");
+            return Err(IncorrectConfigAccount.into());
+        };
+
+        Ok((version, authority))
+    }
+
+    //  account size of this config, discriminator included. `configure` reallocs to it
+    pub fn space(&self) -> usize {
+        8 + self.try_to_vec().map_or(0, |data| data.len())
+    }
+
+    //  stamps and validates a config before it's stored. `stored_config` is the config being
+    //  replaced, `None` for a new one
+    pub fn prepare(&mut self, stored_config: Option<&Config>) -> Result<()> {
+        self.version = CONFIG_VERSION;
+        self.initialized = true;
+
+        require!(self.snipe_tax_bps <= 10_000, ContractError::ValueTooLarge);
+        require!(self.max_buy_fraction_bps <= 10_000, ContractError::ValueTooLarge);
+        require!(self.max_bootstrap_liquidity_bps <= 10_000, ContractError::ValueTooLarge);
+        require!(self.creator_min_hold_bps <= 10_000, ContractError::ValueTooLarge);
+        require!(self.sell_reflection_bps <= 10_000, ContractError::ValueTooLarge);
+        require!(self.loss_sell_fee_bps <= 10_000, ContractError::ValueTooLarge);
+        require!(self.graduation_window_bps <= 10_000, ContractError::ValueTooLarge);
+        require!(self.sell_limit_near_graduation_bps <= 10_000, ContractError::ValueTooLarge);
+        require!(self.fee_mode <= FEE_MODE_ACCRUE, ContractError::ValueInvalid);
+        self.validate_fee_tiers()?;
+        self.validate_token_reserves()?;
+
+        self.validate_fee_ceiling(stored_config.map_or(0, |stored_config| stored_config.max_fee_basis_points))?;
+
+        //  the pending migration counter is program state, updates keep the stored value
+        self.pending_migrations = stored_config.map_or(0, |stored_config| stored_config.pending_migrations);
+        self.active_curves = stored_config.map_or(0, |stored_config| stored_config.active_curves);
+
+        Ok(())
+    }
+
+    //  decodes a config stored by `configure`, instructions that take the config as a raw account
+    //  read it through here. trailing bytes after the config are ignored
+    pub fn load_checked(account: &AccountInfo) -> Result<Config> {
+        if account.owner != &crate::ID {
+            msg!("config {} is owned by {}", account.key(), account.owner);
+            return Err(IncorrectConfigAccount.into());
+        }
+
+        let data = account.try_borrow_data()?;
+        if data.len() < 8 || &data[0..8] != Config::DISCRIMINATOR {
+            msg!("config {} is not a config account", account.key());
+            return Err(IncorrectConfigAccount.into());
+        }
+
+        Config::deserialize(&mut &data[8..]).map_err(|_| {
+            msg!("config {} could not be decoded", account.key());
+            IncorrectConfigAccount.into()
+        })
+    }
+
+    //  writes a config decoded by `load_checked` back in place, the layout and size are unchanged
+    pub fn store(&self, account: &AccountInfo) -> Result<()> {
+        let mut data = account.try_borrow_mut_data()?;
+        self.serialize(&mut &mut data[8..])?;
+
+        Ok(())
+    }
+
+    //  closing the config freezes every curve still trading or waiting for its migration
+    //  curves launched before `active_curves` was tracked aren't counted
+    pub fn check_closable(&self) -> Result<()> {
+        if self.active_curves > 0 || self.pending_migrations > 0 {
+            msg!(
+                "{} curves are trading and {} are waiting for migration",
+                self.active_curves,
+                self.pending_migrations
+            );
+            return Err(ConfigInUse.into());
+        }
+
+        Ok(())
+    }
+
+    //  fee rate of the largest tier the trade size reaches, `None` falls back to the flat fee
+    pub fn fee_bps_for(&self, lamports: u64) -> Option<u16> {
+        self.fee_tiers
+            .iter()
+            .rev()
+            .find(|tier| lamports >= tier.min_lamports)
+            .map(|tier| tier.fee_bps)
+    }
+
+    //  swaps need the user's cost basis pda while any position based feature is on
+    pub fn tracks_positions(&self) -> bool {
+        self.loss_sell_grace || self.max_holders != 0
+    }
+
+    pub fn check_migration_backlog(&self) -> Result<()> {
+        if self.max_pending_migrations != 0 && self.pending_migrations >= self.max_pending_migrations {
+            msg!(
+                "{} curves are waiting for migration, the max is {}",
+                self.pending_migrations,
+                self.max_pending_migrations
+            );
+            return Err(MigrationBacklogFull.into());
+        }
+
+        Ok(())
+    }
+
+    //  called by `create_bonding_curve` once the launch went through
+    pub fn record_launch(&mut self) {
+        self.active_curves = self.active_curves.saturating_add(1);
+    }
+
+    //  called wherever a curve becomes `is_completed`
+    pub fn record_completion(&mut self) {
+        self.active_curves = self.active_curves.saturating_sub(1);
+        self.pending_migrations = self.pending_migrations.saturating_add(1);
+    }
+
+    //  called wherever a curve becomes `is_migrated`
+    pub fn record_migration(&mut self) {
+        self.pending_migrations = self.pending_migrations.saturating_sub(1);
+    }
+
+    pub fn accrues_team_fees(&self) -> bool {
+        self.fee_mode == FEE_MODE_ACCRUE
+    }
+
+    //  the curve starts with the real token reserves inside the virtual ones, virtual reserves
+    //  below the real ones would leave a curve whose real tokens outlast its price
+    pub fn validate_token_reserves(&self) -> Result<()> {
+        //  the first trade divides by the virtual token reserves
+        if self.initial_virtual_token_reserves_config == 0 {
+            msg!("initial_virtual_token_reserves_config: virtual token reserves can't be zero");
+            return Err(ValueInvalid.into());
+        }
+
+        if self.initial_virtual_token_reserves_config < self.initial_real_token_reserves_config {
+            msg!(
+                "initial_virtual_token_reserves_config: {} is below initial_real_token_reserves_config: {}",
+                self.initial_virtual_token_reserves_config,
+                self.initial_real_token_reserves_config
+            );
+            return Err(ValueInvalid.into());
+        }
+
+        Ok(())
+    }
+
+    //  real lamports a curve seeded with `reserve_lamport` raises by selling every real token
+    pub fn max_curve_raise(&self, reserve_lamport: u64) -> u64 {
+        utils::max_curve_raise(
+            reserve_lamport,
+            self.initial_virtual_token_reserves_config,
+            self.initial_real_token_reserves_config,
+        )
+    }
+
+    pub fn buy_fee_bps(&self, lamports: u64) -> u16 {
+        self.fee_bps_for(lamports)
+            .unwrap_or_else(|| percent_to_bps(self.platform_buy_fee))
+    }
+
+    pub fn sell_fee_bps(&self, lamports: u64) -> u16 {
+        self.fee_bps_for(lamports)
+            .unwrap_or_else(|| percent_to_bps(self.platform_sell_fee))
+    }
+
+    pub fn validate_metadata_uri(&self, uri: &str) -> Result<()> {
+        if self.allowed_uri_prefixes.is_empty()
+            || self
+                .allowed_uri_prefixes
+                .iter()
+                .any(|prefix| uri.starts_with(prefix.as_str()))
+        {
+            return Ok(());
+        }
+
+        msg!(
+            "uri: {uri} does not start with one of {:?}",
+            self.allowed_uri_prefixes
+        );
+        Err(InvalidMetadataUri.into())
+    }
+
+    pub fn check_fee_ceiling(&self, name: &str, fee_bps: u16) -> Result<()> {
+        if self.max_fee_basis_points != 0 && fee_bps > self.max_fee_basis_points {
+            msg!(
+                "{name}: {fee_bps} bps exceeds the fee ceiling of {} bps",
+                self.max_fee_basis_points
+            );
+            return Err(FeeExceedsCeiling.into());
+        }
+
+        Ok(())
+    }
+
+    //  `stored_ceiling` is the ceiling of the config being replaced, 0 for a new config
+    pub fn validate_fee_ceiling(&self, stored_ceiling: u16) -> Result<()> {
+        if self.max_fee_basis_points > 10_000 {
+            msg!(
+                "max_fee_basis_points: value {} too large, expected at most 10000",
+                self.max_fee_basis_points
+            );
+            return Err(ValueTooLarge.into());
+        }
+        if stored_ceiling != 0
+            && (self.max_fee_basis_points == 0 || self.max_fee_basis_points > stored_ceiling)
+        {
+            msg!(
+                "max_fee_basis_points: the ceiling of {stored_ceiling} bps can't be raised to {}",
+                self.max_fee_basis_points
+            );
+            return Err(FeeExceedsCeiling.into());
+        }
+
+        self.check_fee_ceiling("platform_buy_fee", percent_to_bps(self.platform_buy_fee))?;
+        self.check_fee_ceiling("platform_sell_fee", percent_to_bps(self.platform_sell_fee))?;
+        for tier in &self.fee_tiers {
+            self.check_fee_ceiling("fee_tiers", tier.fee_bps)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_fee_tiers(&self) -> Result<()> {
+        for (i, tier) in self.fee_tiers.iter().enumerate() {
+            if tier.fee_bps > 10_000 {
+                msg!("fee_tiers[{i}]: fee_bps {} exceeds 10000", tier.fee_bps);
+                return Err(ValueTooLarge.into());
+            }
+            if i > 0 && tier.min_lamports <= self.fee_tiers[i - 1].min_lamports {
+                msg!("fee_tiers[{i}]: min_lamports must be sorted in ascending order");
+                return Err(ValueInvalid.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
 pub enum AmountConfig<T: PartialEq + PartialOrd + Debug> {
     Range { min: Option<T>, max: Option<T> },
@@ -40,18 +481,18 @@ pub enum AmountConfig<T: PartialEq + PartialOrd + Debug> {
 }
 
 impl<T: PartialEq + PartialOrd + Debug> AmountConfig<T> {
-    pub fn validate(&self, value: &T) -> Result<()> {
+    pub fn validate(&self, name: &str, value: &T) -> Result<()> {
         match self {
             Self::Range { min, max } => {
                 if let Some(min) = min {
                     if value < min {
-                        // msg!("value {value:?} too small, expected at least {min:?}");
+                        msg!("{}: value {:?} too small, expected at least {:?}", name, value, min);
                         return Err(ValueTooSmall.into());
                     }
                 }
                 if let Some(max) = max {
                     if value > max {
-                        // msg!("value {value:?} too large, expected at most {max:?}");
+                        msg!("{}: value {:?} too large, expected at most {:?}", name, value, max);
                         return Err(ValueTooLarge.into());
                     }
                 }
@@ -62,7 +503,7 @@ impl<T: PartialEq + PartialOrd + Debug> AmountConfig<T> {
                 if options.contains(value) {
                     Ok(())
                 } else {
-                    // msg!("invalid value {value:?}, expected one of: {options:?}");
+                    msg!("{}: invalid value {:?}, expected one of: {:?}", name, value, options);
                     Err(ValueInvalid.into())
                 }
             }
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+//  what a wallet paid for its tokens of one curve, tracked while `Config::loss_sell_grace` is on
+#[account]
+#[derive(Default, Debug)]
+pub struct CostBasis {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+
+    pub tokens: u64,   //  bought through the curve and not sold yet
+    pub lamports: u64, //  paid for those tokens, fees included
+
+    pub realized_pnl: i64, //  sell proceeds minus the cost of the tokens sold, over all sells
+}
+
+impl CostBasis {
+    //  the owner is only stamped by a buy, a fresh pda or one that only sold still reads as new
+    pub fn is_new(&self) -> bool {
+        self.user == Pubkey::default()
+    }
+
+    //  books an executed trade, a buy stamps the owner and adds everything it took from the
+    //  user, fees and snipe tax included, a sell removes the tokens it sold
+    pub fn record_trade(
+        &mut self,
+        user: Pubkey,
+        mint: Pubkey,
+        direction: u8,
+        amount: u64,
+        amount_out: u64,
+        lamports_paid: u64,
+    ) {
+        if direction == 1 {
+            self.record_sell(amount, amount_out);
+        } else {
+            self.user = user;
+            self.mint = mint;
+            self.record_buy(lamports_paid, amount_out);
+        }
+    }
+
+    pub fn record_buy(&mut self, lamports: u64, tokens: u64) {
+        self.tokens = self.tokens.saturating_add(tokens);
+        self.lamports = self.lamports.saturating_add(lamports);
+    }
+
+    //  removes sold tokens at their average cost, tokens the wallet got elsewhere carry no cost
+    pub fn record_sell(&mut self, tokens: u64, proceeds: u64) {
+        let tokens = tokens.min(self.tokens);
+        let cost = self.cost_of(tokens);
+
+        self.tokens -= tokens;
+        self.lamports -= cost;
+        self.realized_pnl = self
+            .realized_pnl
+            .saturating_add(proceeds as i64 - cost as i64);
+    }
+
+    //  lamports paid for `tokens` at the average entry
+    pub fn cost_of(&self, tokens: u64) -> u64 {
+        if self.tokens == 0 {
+            return 0;
+        }
+
+        (self.lamports as u128 * tokens.min(self.tokens) as u128 / self.tokens as u128) as u64
+    }
+
+    //  only a sell fully covered by recorded buys can count as a loss
+    pub fn is_loss(&self, tokens: u64, proceeds: u64) -> bool {
+        tokens > 0 && tokens <= self.tokens && proceeds < self.cost_of(tokens)
+    }
+}
@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct CreatorStats {
+    pub creator: Pubkey,
+
+    pub last_launch_slot: u64,
+    pub launch_count: u64,
+}
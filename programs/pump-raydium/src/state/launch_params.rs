@@ -0,0 +1,199 @@
+use crate::constants::{MIGRATION_TARGET_RAYDIUM, SUPPORTED_MIGRATION_TARGETS};
+use crate::errors::*;
+use crate::state::config::*;
+use crate::utils::calculate_fee;
+use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
+
+//  `create_bonding_curve` arguments, `None` options fall back to the config or to 0
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TokenLaunchParams {
+    //  launch config
+    pub decimals: u8,
+    pub token_supply: u64,
+    pub reserve_lamport: u64,                 //  initial virtual sol reserves
+    pub graduation_target: Option<u64>,       //  defaults to the config `curve_limit`
+    pub initial_buy_lamports: Option<u64>,    //  creator dev-buy
+    pub min_tokens_out: Option<u64>,          //  dev-buy slippage guard
+    pub team_allocation_bps: Option<u16>,     //  share of the supply minted to the team wallet
+    pub verify_creator: bool,                 //  list the creator as a verified metadata creator
+    pub seller_fee_basis_points: Option<u16>, //  metadata royalty
+    pub fee_override_bps: Option<u16>,        //  replaces the global buy and sell fees
+    pub bootstrap_liquidity_bps: Option<u16>, //  share of the supply set aside for early dex liquidity
+    pub is_mutable: Option<bool>,             //  metadata mutability, defaults to immutable
+    pub migration_target: Option<u8>,         //  graduation dex, defaults to raydium
+
+    //  metadata
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct LaunchAllocation {
+    pub team_tokens: u64,
+    pub bootstrap_tokens: u64,
+    pub curve_tokens: u64, //  minted to the global token account
+}
+
+impl TokenLaunchParams {
+    pub fn is_mutable(&self) -> bool {
+        self.is_mutable.unwrap_or(false)
+    }
+
+    pub fn migration_target(&self) -> u8 {
+        self.migration_target.unwrap_or(MIGRATION_TARGET_RAYDIUM)
+    }
+
+    pub fn decimal_multiplier(&self) -> u64 {
+        10u64.pow(self.decimals as u32) // 10^6 = 1_000_000
+    }
+
+    pub fn graduation_target(&self, global_config: &Config) -> u64 {
+        self.graduation_target.unwrap_or(global_config.curve_limit)
+    }
+
+    //  splits the supply between the team, the bootstrap liquidity and the curve
+    pub fn allocation(&self) -> Result<LaunchAllocation> {
+        let team_tokens = calculate_fee(self.token_supply, self.team_allocation_bps.unwrap_or(0))?;
+        let bootstrap_tokens =
+            calculate_fee(self.token_supply, self.bootstrap_liquidity_bps.unwrap_or(0))?;
+        let curve_tokens = team_tokens
+            .checked_add(bootstrap_tokens)
+            .and_then(|allocated| self.token_supply.checked_sub(allocated))
+            .ok_or(ValueInvalid)?;
+
+        Ok(LaunchAllocation {
+            team_tokens,
+            bootstrap_tokens,
+            curve_tokens,
+        })
+    }
+
+    //  every range check on the launch arguments, returns the supply split
+    pub fn validate(&self, global_config: &Config) -> Result<LaunchAllocation> {
+        let decimals = self.decimals;
+        let token_supply = self.token_supply;
+        let reserve_lamport = self.reserve_lamport;
+
+        //  configs stored before `configure` checked this can still be incoherent
+        global_config.validate_token_reserves()?;
+
+        global_config
+            .token_decimals_config
+            .validate("decimals", &decimals)?;
+
+        if token_supply == 0 {
+            msg!("token_supply: a launch needs tokens to trade");
+            return Err(ValueInvalid.into());
+        }
+
+        let decimal_multiplier = self.decimal_multiplier();
+        let fractional_tokens = token_supply % decimal_multiplier;
+        if fractional_tokens != 0 {
+            msg!("token_supply: {token_supply} is not a whole number of tokens with {decimals} decimals");
+            return Err(ValueInvalid.into());
+        }
+
+        //  reserve_lamport seeds the virtual sol reserves, the curve can't price against zero
+        if reserve_lamport == 0 {
+            msg!("reserve_lamport: initial virtual sol reserves can't be zero");
+            return Err(ValueInvalid.into());
+        }
+
+        // check wether it meets min/max treshold:
+        global_config
+            .lamport_amount_config
+            .validate("reserve_lamport", &reserve_lamport)?;
+
+        global_config
+            .token_supply_config
+            .validate("token_supply", &(token_supply / decimal_multiplier))?;
+
+        //  trades multiply the virtual sol reserves by the virtual token reserves scaled up by 1000
+        //  in u128. the product holds along the curve, so it has to fit at launch with room for
+        //  the rounding, which adds up to one scaled reserve on top
+        let virtual_token_reserves = global_config.initial_virtual_token_reserves_config;
+        let curve_product = (reserve_lamport as u128)
+            .checked_mul(virtual_token_reserves as u128)
+            .and_then(|product| product.checked_mul(1_000))
+            .and_then(|product| product.checked_add(u64::MAX as u128 * 1_000));
+        if curve_product.is_none() {
+            msg!("reserve_lamport: {reserve_lamport} with {virtual_token_reserves} virtual tokens overflows the curve math");
+            return Err(ValueInvalid.into());
+        }
+
+        let team_allocation_bps = self.team_allocation_bps.unwrap_or(0);
+        if team_allocation_bps > 10_000 {
+            msg!("team_allocation_bps: value {team_allocation_bps} too large, expected at most 10000");
+            return Err(ValueTooLarge.into());
+        }
+
+        let bootstrap_liquidity_bps = self.bootstrap_liquidity_bps.unwrap_or(0);
+        if bootstrap_liquidity_bps > global_config.max_bootstrap_liquidity_bps {
+            msg!(
+                "bootstrap_liquidity_bps: value {bootstrap_liquidity_bps} too large, expected at most {}",
+                global_config.max_bootstrap_liquidity_bps
+            );
+            return Err(ValueTooLarge.into());
+        }
+
+        let allocation = self.allocation()?;
+        if allocation.curve_tokens < global_config.initial_real_token_reserves_config {
+            msg!(
+                "team and bootstrap allocations leave {} tokens for the curve, expected at least {}",
+                allocation.curve_tokens,
+                global_config.initial_real_token_reserves_config
+            );
+            return Err(ValueInvalid.into());
+        }
+
+        let seller_fee_basis_points = self.seller_fee_basis_points.unwrap_or(0);
+        if seller_fee_basis_points > 10_000 {
+            msg!("seller_fee_basis_points: value {seller_fee_basis_points} too large, expected at most 10000");
+            return Err(ValueTooLarge.into());
+        }
+
+        if let Some(fee_override_bps) = self.fee_override_bps {
+            if fee_override_bps > 10_000 {
+                msg!("fee_override_bps: value {fee_override_bps} too large, expected at most 10000");
+                return Err(ValueTooLarge.into());
+            }
+            global_config
+                .fee_override_config
+                .validate("fee_override_bps", &fee_override_bps)?;
+            global_config.check_fee_ceiling("fee_override_bps", fee_override_bps)?;
+        }
+
+        let initial_buy_lamports = self.initial_buy_lamports.unwrap_or(0);
+        if initial_buy_lamports < global_config.min_initial_buy_lamports {
+            msg!(
+                "initial_buy_lamports: value {initial_buy_lamports} too small, expected at least {}",
+                global_config.min_initial_buy_lamports
+            );
+            return Err(ValueInvalid.into());
+        }
+
+        let graduation_target = self.graduation_target(global_config);
+        global_config
+            .graduation_target_config
+            .validate("graduation_target", &graduation_target)?;
+
+        //  reserve_lamport also bounds what the curve can raise, a curve that sells out
+        //  below its graduation target could never complete
+        let max_raise = global_config.max_curve_raise(reserve_lamport);
+        if max_raise < graduation_target {
+            msg!("reserve_lamport: {reserve_lamport} raises at most {max_raise} lamports, below the graduation target {graduation_target}");
+            return Err(ValueInvalid.into());
+        }
+
+        global_config.validate_metadata_uri(&self.uri)?;
+
+        let migration_target = self.migration_target();
+        if !SUPPORTED_MIGRATION_TARGETS.contains(&migration_target) {
+            msg!("migration_target: {migration_target} is not supported, expected one of {SUPPORTED_MIGRATION_TARGETS:?}");
+            return Err(UnsupportedMigrationTarget.into());
+        }
+
+        Ok(allocation)
+    }
+}
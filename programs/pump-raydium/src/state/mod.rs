@@ -1,2 +1,7 @@
 pub mod bondingcurve;
 pub mod config;
+pub mod creator_stats;
+pub mod blocklist;
+pub mod bootstrap_liquidity;
+pub mod launch_params;
+pub mod cost_basis;
@@ -1,5 +1,6 @@
+use crate::errors::ContractError;
 use crate::*;
-use anchor_spl::token::{self, Token};
+use anchor_spl::token::{self, spl_token::instruction::AuthorityType, Token};
 use solana_program::program::{invoke, invoke_signed};
 use std::ops::{Div, Mul};
 
@@ -11,6 +12,83 @@ pub fn convert_from_float(value: f64, decimals: u8) -> u64 {
     value.mul(f64::powf(10.0, decimals as f64)) as u64
 }
 
+//  `amount * bps / 10000` with a u128 intermediate, every fee goes through here
+pub fn calculate_fee(amount: u64, bps: u16) -> Result<u64> {
+    let fee = (amount as u128) * (bps as u128) / 10_000;
+
+    u64::try_from(fee).map_err(|_| ContractError::ValueInvalid.into())
+}
+
+//  lamports of one trade, `gross_amount` = `fee_amount` + `net_amount`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    pub gross_amount: u64,
+    pub fee_amount: u64, //  lamports sent to the team wallet
+    pub net_amount: u64, //  lamports the curve takes on buys, lamports the seller receives on sells
+    pub fee_bps: u16,    //  trade fee applied, after overrides
+}
+
+pub fn fee_breakdown(net_amount: u64, fee_amount: u64, fee_bps: u16) -> Result<FeeBreakdown> {
+    let gross_amount = net_amount
+        .checked_add(fee_amount)
+        .ok_or(ContractError::ValueInvalid)?;
+
+    Ok(FeeBreakdown {
+        gross_amount,
+        fee_amount,
+        net_amount,
+        fee_bps,
+    })
+}
+
+//  config fees are stored as percentages, e.g 1.25 -> 125 bps
+pub fn percent_to_bps(percent: f64) -> u16 {
+    (percent * 100.0).round() as u16
+}
+
+//  rejects a trade landing after its signed `deadline` (unix timestamp), none never expires
+pub fn check_deadline(deadline: Option<i64>, now: i64) -> Result<()> {
+    match deadline {
+        Some(deadline) if now > deadline => {
+            msg!("transaction expired at {deadline}, it is now {now}");
+            Err(ContractError::TransactionExpired.into())
+        }
+        _ => Ok(()),
+    }
+}
+
+//  0 buys and 1 sells with an exact input, `SWAP_BUY_EXACT_OUT` needs its `max_input`
+//  any other direction is rejected instead of running as a buy
+pub fn check_swap_direction(direction: u8, max_input: Option<u64>) -> Result<()> {
+    match direction {
+        0 | 1 => Ok(()),
+        constants::SWAP_BUY_EXACT_OUT if max_input.is_some() => Ok(()),
+        constants::SWAP_BUY_EXACT_OUT => {
+            msg!("exact-output buys need a max_input");
+            Err(ContractError::InvalidAmount.into())
+        }
+        _ => {
+            msg!("direction {direction} is not a swap direction");
+            Err(ContractError::InvalidDirection.into())
+        }
+    }
+}
+
+//  every instruction that signs as the global vault checks it here first, so a wrong seed or
+//  bump fails with a clear error instead of a missing signature deep inside a cpi
+pub fn validate_global_vault(global_vault: &AccountInfo, bump: u8) -> Result<()> {
+    let expected =
+        Pubkey::create_program_address(&[constants::GLOBAL.as_bytes(), &[bump]], &crate::ID)
+            .map_err(|_| ContractError::InvalidGlobalVault)?;
+    require_keys_eq!(
+        global_vault.key(),
+        expected,
+        ContractError::InvalidGlobalVault
+    );
+
+    Ok(())
+}
+
 pub fn sol_transfer_from_user<'info>(
     signer: &Signer<'info>,
     destination: AccountInfo<'info>,
@@ -73,6 +151,65 @@ pub fn token_transfer_with_signer<'info>(
     Ok(())
 }
 
+//  mint launch tokens with the global vault as the mint authority
+pub fn mint_with_signer<'info>(
+    mint: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    global_vault: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    token::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::MintTo {
+                mint,
+                to,
+                authority: global_vault,
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+//  revoke the global vault's mint authority, the supply is fixed from here on
+pub fn revoke_mint_authority<'info>(
+    mint: AccountInfo<'info>,
+    global_vault: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token::set_authority(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::SetAuthority {
+                current_authority: global_vault,
+                account_or_mint: mint,
+            },
+            signer_seeds,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )
+}
+
+//  a launch is only announced once the revoke took effect and no extra tokens exist
+pub fn check_launch_mint(mint: &AccountInfo, token_supply: u64) -> Result<()> {
+    let mint_state = token::Mint::try_deserialize(&mut &mint.try_borrow_data()?[..])?;
+    if mint_state.mint_authority.is_some() {
+        msg!("mint {} still has a mint authority", mint.key());
+        return Err(ContractError::MintAuthorityNotRevoked.into());
+    }
+    if mint_state.supply != token_supply {
+        msg!("mint supply {} does not match token_supply {token_supply}", mint_state.supply);
+        return Err(ContractError::SupplyMismatch.into());
+    }
+
+    Ok(())
+}
+
 // transfer sol from PDA
 pub fn sol_transfer_with_signer<'info>(
     source: AccountInfo<'info>,
@@ -88,4 +225,267 @@ pub fn sol_transfer_with_signer<'info>(
         signers_seeds,
     )?;
     Ok(())
-}
\ No newline at end of file
+}
+
+//  wrap native SOL held by the global vault into its WSOL account
+pub fn wrap_sol_with_signer<'info>(
+    global_vault: AccountInfo<'info>,
+    global_wsol_account: AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    token_program: &Program<'info, Token>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    //  the vault has to stay rent exempt after the lamports move out
+    let min_balance = Rent::get()?.minimum_balance(global_vault.data_len());
+    let remaining = global_vault
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ContractError::InsufficientSol)?;
+    require!(remaining >= min_balance, ContractError::InsufficientSol);
+
+    sol_transfer_with_signer(
+        global_vault,
+        global_wsol_account.clone(),
+        system_program,
+        signer_seeds,
+        amount,
+    )?;
+
+    //  the WSOL account keeps its own rent, only the synced lamports become token balance
+    token::sync_native(CpiContext::new(
+        token_program.to_account_info(),
+        token::SyncNative {
+            account: global_wsol_account,
+        },
+    ))?;
+
+    Ok(())
+}
+
+//  unwrap all WSOL back into the global vault
+//  this closes the WSOL account, its rent is returned to the vault as well
+pub fn unwrap_sol_with_signer<'info>(
+    global_wsol_account: AccountInfo<'info>,
+    global_vault: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token::CloseAccount {
+            account: global_wsol_account,
+            destination: global_vault.clone(),
+            authority: global_vault,
+        },
+        signer_seeds,
+    ))?;
+
+    Ok(())
+}
+
+//  pays a buy with the user's WSOL by closing their WSOL account into the curve vault
+//  WSOL can't be unwrapped partially, the whole balance and the account rent move, and the
+//  buy hands back what it doesn't spend. returns the lamports now waiting in the vault
+pub fn unwrap_user_wsol<'info>(
+    user_wsol_account: AccountInfo<'info>,
+    curve_vault: AccountInfo<'info>,
+    user: &Signer<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<u64> {
+    let lamports = user_wsol_account.lamports();
+
+    token::close_account(CpiContext::new(
+        token_program.to_account_info(),
+        token::CloseAccount {
+            account: user_wsol_account,
+            destination: curve_vault,
+            authority: user.to_account_info(),
+        },
+    ))?;
+
+    Ok(lamports)
+}
+
+//  real lamports a curve raises by selling all `real_tokens` from its virtual reserves:
+//  `virtual_sol * real / (virtual_tokens - real)`, unbounded when no virtual tokens sit behind the real ones
+pub fn max_curve_raise(virtual_sol: u64, virtual_tokens: u64, real_tokens: u64) -> u64 {
+    let backing_tokens = virtual_tokens.saturating_sub(real_tokens) as u128;
+    if backing_tokens == 0 {
+        return u64::MAX;
+    }
+
+    u64::try_from(virtual_sol as u128 * real_tokens as u128 / backing_tokens).unwrap_or(u64::MAX)
+}
+
+//  floor(sqrt(value)), newton's method
+pub fn integer_sqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+
+    x
+}
+
+//  raydium locks 10^lp_decimals of the initial lp, `initialize2` fails unless
+//  sqrt(pc * coin) is above that, returns the lp left to the depositor
+pub fn raydium_initial_lp(coin_amount: u64, pc_amount: u64, lp_decimals: u8) -> Option<u64> {
+    let liquidity = integer_sqrt((coin_amount as u128) * (pc_amount as u128));
+    let locked = 10u128.checked_pow(lp_decimals as u32)?;
+
+    u64::try_from(liquidity.checked_sub(locked)?)
+        .ok()
+        .filter(|lp| *lp > 0)
+}
+
+//  LP tokens `migrate` burns out of what `initialize2` mints to the global vault, checked before
+//  the pool is opened so a lock the deposit can't cover fails the migration up front
+pub fn lp_to_lock(
+    locked_lp_amount: u64,
+    coin_amount: u64,
+    pc_amount: u64,
+    lp_decimals: u8,
+) -> Result<u64> {
+    if locked_lp_amount == 0 {
+        return Ok(0);
+    }
+
+    let minted_lp = raydium_initial_lp(coin_amount, pc_amount, lp_decimals).unwrap_or(0);
+    if minted_lp < locked_lp_amount {
+        msg!("pool mints {minted_lp} LP tokens, expected at least {locked_lp_amount} to lock");
+        return Err(ContractError::InsufficientLpToLock.into());
+    }
+
+    Ok(locked_lp_amount)
+}
+
+//  checks the failure modes of `initialize2` we can see before calling it
+pub fn check_raydium_pool(
+    amm: &AccountInfo,
+    coin_amount: u64,
+    pc_amount: u64,
+    lp_decimals: u8,
+) -> Result<()> {
+    if amm.data_len() > 0 || amm.lamports() > 0 {
+        msg!("raydium pool {} already exists", amm.key());
+        return Err(ContractError::PoolAlreadyExists.into());
+    }
+
+    if raydium_initial_lp(coin_amount, pc_amount, lp_decimals).is_none() {
+        msg!(
+            "raydium pool needs sqrt(coin * pc) above 10^{lp_decimals}, got coin: {coin_amount} pc: {pc_amount}"
+        );
+        return Err(ContractError::MigrationAmountTooLow.into());
+    }
+
+    Ok(())
+}
+
+//  balance of a raydium pool vault, only trusted from a token account of the expected mint
+pub fn pool_vault_amount(vault: &AccountInfo, mint: &Pubkey) -> Result<u64> {
+    if vault.owner != &token::ID || token::accessor::mint(vault)? != *mint {
+        msg!("pool vault {} is not a {mint} token account", vault.key());
+        return Err(ContractError::RaydiumCpiFailed.into());
+    }
+
+    token::accessor::amount(vault)
+}
+
+//  LP balance of the global vault's LP account, 0 until `initialize2` has created it
+//  the account only exists after the cpi, so its mint and authority are checked here
+//  instead of as account constraints, before anything is burned from it
+pub fn lp_account_balance(
+    lp_account: &AccountInfo,
+    lp_mint: &Pubkey,
+    authority: &Pubkey,
+) -> Result<u64> {
+    if lp_account.owner != &token::ID || lp_account.data_len() != token::TokenAccount::LEN {
+        return Ok(0);
+    }
+
+    let lp_account = token::TokenAccount::try_deserialize(&mut &lp_account.try_borrow_data()?[..])?;
+    if lp_account.mint != *lp_mint || lp_account.owner != *authority {
+        msg!(
+            "LP account of mint {} owned by {}, expected {lp_mint} and {authority}",
+            lp_account.mint,
+            lp_account.owner
+        );
+        return Err(ContractError::InvalidTokenAccount.into());
+    }
+
+    Ok(lp_account.amount)
+}
+
+//  maps the errors a raydium cpi hands back to the caller onto crate errors
+//  keepers retry on `MigrationAmountTooLow`, skip on `PoolAlreadyExists`, alert otherwise
+pub fn map_raydium_error(err: ProgramError) -> ContractError {
+    msg!("raydium cpi failed: {err} ({})", u64::from(err.clone()));
+
+    match err {
+        ProgramError::AccountAlreadyInitialized => ContractError::PoolAlreadyExists,
+        ProgramError::InsufficientFunds => ContractError::MigrationAmountTooLow,
+        _ => ContractError::RaydiumCpiFailed,
+    }
+}
+
+//  pre-flights and signs a raydium `initialize2`, every failure comes back as a crate error
+pub fn invoke_raydium_initialize2(
+    ix: &solana_program::instruction::Instruction,
+    accounts: &[AccountInfo],
+    signer_seeds: &[&[&[u8]]],
+    amm: &AccountInfo,
+    coin_amount: u64,
+    pc_amount: u64,
+    lp_decimals: u8,
+) -> Result<()> {
+    check_raydium_pool(amm, coin_amount, pc_amount, lp_decimals)?;
+
+    invoke_signed(ix, accounts, signer_seeds).map_err(|err| map_raydium_error(err).into())
+}
+
+//  emits through a self-CPI when built with `event-cpi`, so events survive log truncation
+//  the instruction's accounts struct needs `#[cfg_attr(feature = "event-cpi", event_cpi)]`
+#[macro_export]
+macro_rules! emit_event {
+    ($accounts:expr, $event:expr) => {{
+        #[cfg(feature = "event-cpi")]
+        $crate::utils::emit_cpi_event(&$accounts.event_authority, &$event)?;
+        #[cfg(not(feature = "event-cpi"))]
+        anchor_lang::prelude::emit!($event);
+    }};
+}
+
+#[cfg(feature = "event-cpi")]
+pub fn emit_cpi_event<'info, E: anchor_lang::Event>(
+    event_authority: &AccountInfo<'info>,
+    event: &E,
+) -> Result<()> {
+    use crate::constants::EVENT_AUTHORITY;
+    use anchor_lang::event::EVENT_IX_TAG_LE;
+
+    let (_, bump) = Pubkey::find_program_address(&[EVENT_AUTHORITY.as_bytes()], &crate::ID);
+    let ix_data: Vec<u8> = EVENT_IX_TAG_LE
+        .iter()
+        .copied()
+        .chain(event.data())
+        .collect();
+    let ix = solana_program::instruction::Instruction::new_with_bytes(
+        crate::ID,
+        &ix_data,
+        vec![AccountMeta::new_readonly(event_authority.key(), true)],
+    );
+    invoke_signed(
+        &ix,
+        &[event_authority.clone()],
+        &[&[EVENT_AUTHORITY.as_bytes(), &[bump]]],
+    )?;
+
+    Ok(())
+}
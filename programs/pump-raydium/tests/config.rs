@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use pump_raydium::{errors::ContractError, state::config::AmountConfig};
+
+fn assert_error(result: Result<()>, expected: ContractError) {
+    assert_eq!(result.unwrap_err(), Error::from(expected));
+}
+
+#[test]
+fn lamport_amount_range() {
+    let config = AmountConfig::Range {
+        min: Some(15_000_000_000u64),
+        max: Some(20_000_000_000u64),
+    };
+
+    assert_error(config.validate("reserve_lamport", &0), ContractError::ValueTooSmall);
+    assert_error(
+        config.validate("reserve_lamport", &14_999_999_999),
+        ContractError::ValueTooSmall,
+    );
+    assert!(config.validate("reserve_lamport", &15_000_000_000).is_ok());
+    assert!(config.validate("reserve_lamport", &17_500_000_000).is_ok());
+    assert!(config.validate("reserve_lamport", &20_000_000_000).is_ok());
+    assert_error(
+        config.validate("reserve_lamport", &20_000_000_001),
+        ContractError::ValueTooLarge,
+    );
+    assert_error(config.validate("reserve_lamport", &u64::MAX), ContractError::ValueTooLarge);
+}
+
+#[test]
+fn token_supply_range() {
+    let config = AmountConfig::Range {
+        min: Some(1_000_000_000u64),
+        max: Some(1_000_000_000u64),
+    };
+
+    assert_error(config.validate("token_supply", &999_999_999), ContractError::ValueTooSmall);
+    assert!(config.validate("token_supply", &1_000_000_000).is_ok());
+    assert_error(config.validate("token_supply", &1_000_000_001), ContractError::ValueTooLarge);
+}
+
+#[test]
+fn open_ended_ranges() {
+    let no_max = AmountConfig::Range {
+        min: Some(1u64),
+        max: None,
+    };
+    assert_error(no_max.validate("token_supply", &0), ContractError::ValueTooSmall);
+    assert!(no_max.validate("token_supply", &u64::MAX).is_ok());
+
+    let no_min = AmountConfig::Range {
+        min: None,
+        max: Some(1_000u64),
+    };
+    assert!(no_min.validate("token_supply", &0).is_ok());
+    assert_error(no_min.validate("token_supply", &1_001), ContractError::ValueTooLarge);
+
+    let unbounded = AmountConfig::<u64>::Range {
+        min: None,
+        max: None,
+    };
+    assert!(unbounded.validate("token_supply", &0).is_ok());
+    assert!(unbounded.validate("token_supply", &u64::MAX).is_ok());
+}
+
+#[test]
+fn token_decimals_enum() {
+    let config = AmountConfig::Enum(vec![6u8, 9]);
+
+    assert!(config.validate("decimals", &6).is_ok());
+    assert!(config.validate("decimals", &9).is_ok());
+    assert_error(config.validate("decimals", &0), ContractError::ValueInvalid);
+    assert_error(config.validate("decimals", &7), ContractError::ValueInvalid);
+    assert_error(config.validate("decimals", &u8::MAX), ContractError::ValueInvalid);
+
+    let empty = AmountConfig::<u8>::Enum(vec![]);
+    assert_error(empty.validate("decimals", &6), ContractError::ValueInvalid);
+}
+
+#[test]
+fn token_decimals_range() {
+    let config = AmountConfig::Range {
+        min: Some(6u8),
+        max: Some(9u8),
+    };
+
+    assert_error(config.validate("decimals", &5), ContractError::ValueTooSmall);
+    assert!(config.validate("decimals", &6).is_ok());
+    assert!(config.validate("decimals", &9).is_ok());
+    assert_error(config.validate("decimals", &10), ContractError::ValueTooLarge);
+}
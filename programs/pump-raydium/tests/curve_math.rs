@@ -0,0 +1,1264 @@
+use proptest::prelude::*;
+use anchor_lang::{
+    error::Error,
+    prelude::{AccountInfo, Pubkey},
+    AccountSerialize, AnchorSerialize, Discriminator,
+};
+use pump_raydium::constants::{
+    FEE_MODE_ACCRUE, MIGRATION_TARGET_METEORA, MIGRATION_TARGET_RAYDIUM, SWAP_BUY_EXACT_OUT,
+};
+use pump_raydium::errors::ContractError;
+use pump_raydium::utils::{calculate_fee, check_deadline, check_swap_direction};
+use pump_raydium::state::{
+    bondingcurve::{BondingCurve, LegacyBondingCurve, MigrationAmounts},
+    config::{
+        AmountConfig, Config, LegacyConfig, MigrationSurplus, CONFIG_VERSION, LEGACY_CONFIG_VERSION,
+    },
+    cost_basis::CostBasis,
+    launch_params::TokenLaunchParams,
+};
+
+fn curve(virtual_sol_reserves: u64, virtual_token_reserves: u64) -> BondingCurve {
+    BondingCurve {
+        virtual_sol_reserves,
+        virtual_token_reserves,
+        real_token_reserves: virtual_token_reserves,
+        ..Default::default()
+    }
+}
+
+fn config() -> Config {
+    Config {
+        version: CONFIG_VERSION,
+        authority: Pubkey::default(),
+        migration_authority: Pubkey::default(),
+        team_wallet: Pubkey::default(),
+        migration_wallet: Pubkey::default(),
+        init_bonding_curve: 80.0,
+        platform_buy_fee: 1.0,
+        platform_sell_fee: 1.0,
+        platform_migration_fee: 1.0,
+        curve_limit: 85_000_000_000,
+        lamport_amount_config: AmountConfig::Range { min: None, max: None },
+        graduation_target_config: AmountConfig::Range { min: None, max: None },
+        token_supply_config: AmountConfig::Range { min: None, max: None },
+        token_decimals_config: AmountConfig::Range { min: None, max: None },
+        initial_virtual_token_reserves_config: 1_073_000_191_000_000,
+        initial_virtual_sol_reserves_config: 30_000_000_000,
+        initial_real_token_reserves_config: 793_100_000_000_000,
+        initial_raydium_token_reserves: 206_900_000_000_000,
+        initial_raydium_sol_amount: 79_000_000_000,
+        snipe_tax_slots: 0,
+        snipe_tax_bps: 0,
+        fee_tiers: vec![],
+        emit_reserves: false,
+        min_initial_buy_lamports: 0,
+        min_slots_between_launches: 0,
+        max_buy_fraction_bps: 0,
+        sell_fee_from_reserves: false,
+        max_sol_reserves: 0,
+        enforce_blocklist: false,
+        require_slippage_protection: false,
+        fee_override_config: AmountConfig::Range { min: None, max: None },
+        buys_enabled: true,
+        sells_enabled: true,
+        max_bootstrap_liquidity_bps: 0,
+        creator_min_hold_bps: 0,
+        allowed_uri_prefixes: vec![],
+        locked_lp_amount: 0,
+        sell_reflection_bps: 0,
+        max_pending_migrations: 0,
+        pending_migrations: 0,
+        active_curves: 0,
+        loss_sell_grace: false,
+        loss_sell_fee_bps: 0,
+        graduation_window_bps: 0,
+        sell_limit_near_graduation_bps: 0,
+        max_holders: 0,
+        migration_surplus: MigrationSurplus::AddToPool,
+        min_buy_lamports: 0,
+        migration_delay_seconds: 0,
+        fee_mode: 0,
+        max_fee_basis_points: 0,
+        require_verified_creator: false,
+        initialized: true,
+    }
+}
+
+proptest! {
+    #[test]
+    fn buy_then_sell_never_returns_more_sol(
+        virtual_sol_reserves in 1_000_000_000u64..10_000_000_000_000,
+        virtual_token_reserves in 1_000_000_000_000u64..10_000_000_000_000_000,
+        sol_amount in 1u64..1_000_000_000_000,
+    ) {
+        let mut curve = curve(virtual_sol_reserves, virtual_token_reserves);
+
+        let buy = curve.apply_buy(sol_amount).expect("buy overflowed");
+        prop_assert!(buy.sol_amount <= sol_amount);
+
+        // dust buys can round down to zero tokens, which cannot be sold back
+        prop_assume!(buy.token_amount > 0);
+
+        let sell = curve.apply_sell(buy.token_amount).expect("sell overflowed");
+        prop_assert!(sell.sol_amount <= buy.sol_amount);
+
+        prop_assert!(curve.virtual_sol_reserves >= virtual_sol_reserves);
+        prop_assert_eq!(curve.virtual_token_reserves, virtual_token_reserves);
+        prop_assert_eq!(curve.real_sol_reserves, buy.sol_amount - sell.sol_amount);
+    }
+
+    #[test]
+    fn sell_never_drains_the_reserves(
+        virtual_sol_reserves in 1_000_000_000u64..10_000_000_000_000,
+        virtual_token_reserves in 1_000_000_000_000u64..10_000_000_000_000_000,
+        sol_amount in 1u64..1_000_000_000_000,
+        sell_fraction in 1u64..=100,
+    ) {
+        let mut curve = curve(virtual_sol_reserves, virtual_token_reserves);
+
+        let buy = curve.apply_buy(sol_amount).expect("buy overflowed");
+        let token_amount = buy.token_amount * sell_fraction / 100;
+        prop_assume!(token_amount > 0);
+
+        let sell = curve.apply_sell(token_amount).expect("sell overflowed");
+        prop_assert!(sell.sol_amount <= buy.sol_amount);
+        prop_assert!(curve.real_sol_reserves <= buy.sol_amount);
+        prop_assert!(curve.virtual_sol_reserves > 0);
+        prop_assert!(curve.virtual_token_reserves > 0);
+    }
+
+    #[test]
+    fn exact_out_buy_costs_the_smallest_sol_amount(
+        virtual_sol_reserves in 1_000_000_000u64..10_000_000_000_000,
+        virtual_token_reserves in 1_000_000_000_000u64..10_000_000_000_000_000,
+        token_fraction in 1u64..10_000,
+    ) {
+        let curve = curve(virtual_sol_reserves, virtual_token_reserves);
+        let token_amount = virtual_token_reserves / 10_000 * token_fraction;
+
+        let sol_amount = curve.get_sol_for_buy_tokens(token_amount).expect("inverse overflowed");
+        prop_assert!(curve.get_tokens_for_buy_sol(sol_amount).unwrap() >= token_amount);
+        if sol_amount > 1 {
+            prop_assert!(curve.get_tokens_for_buy_sol(sol_amount - 1).unwrap() < token_amount);
+        }
+    }
+}
+
+#[test]
+fn buy_rounds_tokens_down() {
+    let (virtual_sol_reserves, virtual_token_reserves, sol_amount) =
+        (1_000_000_007u64, 1_073_000_191_000_003u64, 333_333_337u64);
+    let curve = curve(virtual_sol_reserves, virtual_token_reserves);
+
+    let numerator = virtual_token_reserves as u128 * sol_amount as u128;
+    let denominator = virtual_sol_reserves as u128 + sol_amount as u128;
+    assert_ne!(numerator % denominator, 0);
+
+    let token_amount = curve.get_tokens_for_buy_sol(sol_amount).unwrap();
+    assert_eq!(token_amount as u128, numerator / denominator);
+}
+
+#[test]
+fn sell_rounds_sol_down() {
+    let (virtual_sol_reserves, virtual_token_reserves, token_amount) =
+        (30_000_000_011u64, 1_073_000_191_000_003u64, 12_345_678_901u64);
+    let curve = curve(virtual_sol_reserves, virtual_token_reserves);
+
+    let numerator = virtual_sol_reserves as u128 * token_amount as u128;
+    let denominator = virtual_token_reserves as u128 + token_amount as u128;
+    assert_ne!(numerator % denominator, 0);
+
+    let sol_amount = curve.get_sol_for_sell_tokens(token_amount).unwrap();
+    assert_eq!(sol_amount as u128, numerator / denominator);
+}
+
+#[test]
+fn sell_fee_from_proceeds_keeps_reserves() {
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    curve.apply_buy(5_000_000_000).unwrap();
+    let sell = curve.apply_sell(100_000_000_000_000).unwrap();
+    let (virtual_sol_reserves, real_sol_reserves) =
+        (curve.virtual_sol_reserves, curve.real_sol_reserves);
+
+    let fee_amount = sell.sol_amount / 100;
+    let seller_amount = curve
+        .settle_sell_fee(sell.sol_amount, fee_amount, false)
+        .unwrap();
+
+    assert_eq!(seller_amount, sell.sol_amount - fee_amount);
+    assert_eq!(curve.virtual_sol_reserves, virtual_sol_reserves);
+    assert_eq!(curve.real_sol_reserves, real_sol_reserves);
+}
+
+#[test]
+fn sell_fee_from_reserves_lowers_reserves() {
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    curve.apply_buy(5_000_000_000).unwrap();
+    let sell = curve.apply_sell(100_000_000_000_000).unwrap();
+    let (virtual_sol_reserves, real_sol_reserves) =
+        (curve.virtual_sol_reserves, curve.real_sol_reserves);
+
+    let fee_amount = sell.sol_amount / 100;
+    let seller_amount = curve
+        .settle_sell_fee(sell.sol_amount, fee_amount, true)
+        .unwrap();
+
+    assert_eq!(seller_amount, sell.sol_amount);
+    assert_eq!(curve.virtual_sol_reserves, virtual_sol_reserves - fee_amount);
+    assert_eq!(curve.real_sol_reserves, real_sol_reserves - fee_amount);
+}
+
+#[test]
+fn reserve_lamport_sets_the_opening_price() {
+    let mut cheap = BondingCurve::default();
+    cheap.init_reserves(30_000_000_000, 1_073_000_000_000_000, 793_100_000_000_000);
+    let mut pricey = BondingCurve::default();
+    pricey.init_reserves(60_000_000_000, 1_073_000_000_000_000, 793_100_000_000_000);
+
+    assert_eq!(cheap.init_lamport, cheap.virtual_sol_reserves);
+    assert_eq!(cheap.real_sol_reserves, 0);
+    assert!(
+        cheap.get_tokens_for_buy_sol(1_000_000_000).unwrap()
+            > pricey.get_tokens_for_buy_sol(1_000_000_000).unwrap()
+    );
+}
+
+#[test]
+fn dev_buy_reverts_on_manipulated_reserves() {
+    let dev_buy = 1_000_000_000;
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    let min_tokens_out = curve.get_tokens_for_buy_sol(dev_buy).unwrap() * 99 / 100;
+
+    //  a sniper buys ahead of the creator
+    curve.apply_buy(10_000_000_000).unwrap();
+
+    let token_amount = curve.get_tokens_for_buy_sol(dev_buy).unwrap();
+    assert!(BondingCurve::check_min_out(token_amount, min_tokens_out).is_err());
+    assert!(BondingCurve::check_min_out(min_tokens_out, min_tokens_out).is_ok());
+}
+
+#[test]
+fn spot_price_keeps_sub_lamport_precision() {
+    let curve = curve(30_000_000_000, 1_073_000_191_000_000);
+
+    assert_eq!(curve.spot_price_lamports_per_token_scaled(0), 0);
+    assert_eq!(curve.spot_price_lamports_per_token_scaled(9), 27_958);
+    assert_eq!(BondingCurve::default().spot_price_lamports_per_token_scaled(9), 0);
+}
+
+#[test]
+fn burn_unsold_keeps_only_the_pool_reserve() {
+    let config = config();
+    let curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    let token_balance = 250_000_000_000_000;
+
+    let amounts = curve.migration_amounts(&config, token_balance, false).unwrap();
+    assert_eq!(amounts.token_amount, token_balance);
+    assert_eq!(amounts.burn_amount, 0);
+
+    let amounts = curve.migration_amounts(&config, token_balance, true).unwrap();
+    assert_eq!(amounts.token_amount, 206_900_000_000_000);
+    assert_eq!(amounts.burn_amount, token_balance - 206_900_000_000_000);
+}
+
+#[test]
+fn clamp_keeps_the_requested_rate() {
+    let config = config();
+    let amount = 10_000_000_000;
+    let fresh = curve(30_000_000_000, 1_073_000_191_000_000);
+    let minimum_receive_amount = fresh.quote(&config, amount, 0, 0).unwrap();
+
+    //  the price moved against the buyer before the trade landed
+    let mut moved = fresh.clone();
+    moved.apply_buy(1_000_000_000).unwrap();
+    assert!(moved.quote(&config, amount, 0, 0).unwrap() < minimum_receive_amount);
+
+    let executed = moved.clamp_to_slippage(&config, amount, 0, minimum_receive_amount, 0);
+    assert!(executed < amount);
+    let amount_out = moved.quote(&config, executed, 0, 0).unwrap() as u128;
+    assert!(amount_out * amount as u128 >= minimum_receive_amount as u128 * executed as u128);
+
+    assert_eq!(fresh.clamp_to_slippage(&config, amount, 0, minimum_receive_amount, 0), amount);
+}
+
+#[test]
+fn locked_migration_amounts_ignore_later_fee_changes() {
+    let mut config = config();
+    let mut curve = BondingCurve {
+        real_sol_reserves: 85_000_000_000,
+        ..Default::default()
+    };
+    curve.lock_migration_amounts(&config, 206_900_000_000_000).unwrap();
+    let locked = curve.locked_migration_amounts(&config, false);
+
+    config.platform_migration_fee = 5.0;
+    let after_change = curve.locked_migration_amounts(&config, false);
+
+    assert_eq!(locked.sol_amount, 84_150_000_000);
+    assert_eq!(after_change.sol_amount, locked.sol_amount);
+    assert_eq!(after_change.token_amount, 206_900_000_000_000);
+}
+
+#[test]
+fn sell_slippage_is_checked_after_fees() {
+    let config = config();
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    curve.apply_buy(5_000_000_000).unwrap();
+    let token_amount = 100_000_000_000_000;
+
+    let gross = curve.get_sol_for_sell_tokens(token_amount).unwrap();
+    let net = curve.clone().settle_sell(&config, token_amount, 0).unwrap().seller_amount;
+    assert!(net < gross);
+
+    //  the gross output would pass, the fee pushes the payout below the bound
+    assert!(curve.clone().settle_sell(&config, token_amount, gross).is_err());
+    assert!(curve.settle_sell(&config, token_amount, net).is_ok());
+}
+
+#[test]
+fn sell_settlement_reports_the_applied_fee_bps() {
+    let config = config();
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    curve.apply_buy(5_000_000_000).unwrap();
+    let token_amount = 100_000_000_000_000;
+    let gross = curve.get_sol_for_sell_tokens(token_amount).unwrap();
+
+    let settlement = curve.clone().settle_sell(&config, token_amount, 0).unwrap();
+    assert_eq!(settlement.fee_bps, 100);
+    assert_eq!(settlement.fee_amount + settlement.seller_amount, gross);
+
+    let discounted = curve
+        .clone()
+        .settle_sell_with_fee(&config, token_amount, 0, Some(25))
+        .unwrap();
+    assert_eq!(discounted.fee_bps, 25);
+
+    curve.fee_override_bps = Some(300);
+    assert_eq!(curve.buy_fee_bps(&config, 1_000_000_000), 300);
+    assert_eq!(curve.settle_sell(&config, token_amount, 0).unwrap().fee_bps, 300);
+}
+
+#[test]
+fn launch_params_split_the_supply() {
+    let config = config();
+    let mut params = TokenLaunchParams {
+        decimals: 6,
+        token_supply: 1_000_000_000_000_000,
+        reserve_lamport: 30_000_000_000,
+        graduation_target: None,
+        initial_buy_lamports: None,
+        min_tokens_out: None,
+        team_allocation_bps: Some(2_000),
+        verify_creator: false,
+        seller_fee_basis_points: None,
+        fee_override_bps: None,
+        bootstrap_liquidity_bps: None,
+        is_mutable: None,
+        migration_target: None,
+        name: "Test".to_string(),
+        symbol: "TEST".to_string(),
+        uri: String::new(),
+    };
+
+    let allocation = params.validate(&config).unwrap();
+    assert_eq!(allocation.team_tokens, 200_000_000_000_000);
+    assert_eq!(allocation.bootstrap_tokens, 0);
+    assert_eq!(allocation.curve_tokens, 800_000_000_000_000);
+    assert_eq!(params.graduation_target(&config), config.curve_limit);
+
+    //  leaves less than `initial_real_token_reserves_config` for the curve
+    params.team_allocation_bps = Some(2_100);
+    assert!(params.validate(&config).is_err());
+
+    params.team_allocation_bps = None;
+    params.token_supply += 1;
+    assert!(params.validate(&config).is_err());
+
+    //  degenerate launches are rejected before any account is created
+    params.token_supply = 0;
+    assert!(params.validate(&config).is_err());
+
+    params.token_supply = 1_000_000_000_000_000;
+    params.reserve_lamport = 0;
+    assert!(params.validate(&config).is_err());
+}
+
+#[test]
+fn launch_params_only_accept_supported_migration_targets() {
+    let config = config();
+    let mut params = TokenLaunchParams {
+        decimals: 6,
+        token_supply: 1_000_000_000_000_000,
+        reserve_lamport: 30_000_000_000,
+        graduation_target: None,
+        initial_buy_lamports: None,
+        min_tokens_out: None,
+        team_allocation_bps: None,
+        verify_creator: false,
+        seller_fee_basis_points: None,
+        fee_override_bps: None,
+        bootstrap_liquidity_bps: None,
+        is_mutable: None,
+        migration_target: None,
+        name: "Test".to_string(),
+        symbol: "TEST".to_string(),
+        uri: String::new(),
+    };
+    assert_eq!(params.migration_target(), MIGRATION_TARGET_RAYDIUM);
+    params.validate(&config).unwrap();
+
+    params.migration_target = Some(MIGRATION_TARGET_METEORA);
+    assert_eq!(
+        params.validate(&config).unwrap_err(),
+        Error::from(ContractError::UnsupportedMigrationTarget)
+    );
+
+    params.migration_target = Some(u8::MAX);
+    assert!(params.validate(&config).is_err());
+}
+
+#[test]
+fn reserve_lamport_has_to_reach_the_graduation_target() {
+    let config = config();
+    let mut params = TokenLaunchParams {
+        decimals: 6,
+        token_supply: 1_000_000_000_000_000,
+        reserve_lamport: 30_000_000_000,
+        graduation_target: None,
+        initial_buy_lamports: None,
+        min_tokens_out: None,
+        team_allocation_bps: None,
+        verify_creator: false,
+        seller_fee_basis_points: None,
+        fee_override_bps: None,
+        bootstrap_liquidity_bps: None,
+        is_mutable: None,
+        migration_target: None,
+        name: "Test".to_string(),
+        symbol: "TEST".to_string(),
+        uri: String::new(),
+    };
+    params.validate(&config).unwrap();
+
+    //  buying out the curve costs what `max_curve_raise` promises
+    let max_raise = config.max_curve_raise(params.reserve_lamport);
+    assert!(max_raise >= config.curve_limit);
+    let mut curve = BondingCurve::default();
+    curve.init_reserves(
+        params.reserve_lamport,
+        config.initial_virtual_token_reserves_config,
+        config.initial_real_token_reserves_config,
+    );
+    let sell_out = curve.get_sol_for_buy_tokens(curve.real_token_reserves).unwrap();
+    assert!(sell_out.abs_diff(max_raise) <= 1);
+
+    //  a smaller seed sells out before the curve can graduate
+    params.reserve_lamport = 25_000_000_000;
+    assert!(config.max_curve_raise(params.reserve_lamport) < config.curve_limit);
+    assert_eq!(
+        params.validate(&config).unwrap_err(),
+        Error::from(ContractError::ValueInvalid)
+    );
+    params.graduation_target = Some(config.max_curve_raise(params.reserve_lamport));
+    params.validate(&config).unwrap();
+
+    //  reserves replaced by `admin_set_reserves` go through the same checks
+    curve.graduation_target = config.curve_limit;
+    let virtual_tokens = config.initial_virtual_token_reserves_config;
+    curve.validate_reserves(30_000_000_000, virtual_tokens).unwrap();
+    assert_eq!(
+        curve.validate_reserves(25_000_000_000, virtual_tokens),
+        Err(ContractError::ValueInvalid.into())
+    );
+    assert_eq!(
+        curve.validate_reserves(30_000_000_000, curve.real_token_reserves - 1),
+        Err(ContractError::ValueInvalid.into())
+    );
+}
+
+#[test]
+fn launches_reject_reserves_that_overflow_the_curve_product() {
+    let mut config = config();
+    config.initial_virtual_token_reserves_config = u64::MAX / 2;
+    let mut params = TokenLaunchParams {
+        decimals: 6,
+        token_supply: 1_000_000_000_000_000,
+        reserve_lamport: u64::MAX,
+        graduation_target: None,
+        initial_buy_lamports: None,
+        min_tokens_out: None,
+        team_allocation_bps: None,
+        verify_creator: false,
+        seller_fee_basis_points: None,
+        fee_override_bps: None,
+        bootstrap_liquidity_bps: None,
+        is_mutable: None,
+        migration_target: None,
+        name: "Test".to_string(),
+        symbol: "TEST".to_string(),
+        uri: String::new(),
+    };
+    //  u64::MAX * u64::MAX / 2 * 1000 is past u128::MAX
+    assert_eq!(
+        params.validate(&config).unwrap_err(),
+        Error::from(ContractError::ValueInvalid)
+    );
+
+    //  the largest seed that fits still prices trades both ways
+    params.reserve_lamport =
+        ((u128::MAX - u64::MAX as u128 * 1_000) / 1_000 / (u64::MAX / 2) as u128) as u64;
+    params.validate(&config).unwrap();
+    let mut curve = BondingCurve::default();
+    curve.init_reserves(
+        params.reserve_lamport,
+        config.initial_virtual_token_reserves_config,
+        config.initial_real_token_reserves_config,
+    );
+    assert!(curve.get_tokens_for_buy_sol(1_000_000_000).is_some());
+    assert!(curve.get_sol_for_sell_tokens(1_000_000).is_some());
+    assert!(curve.get_sol_for_buy_tokens(1_000_000).is_some());
+}
+
+#[test]
+fn reserves_reconcile_with_the_vault() {
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    let vault_before = 10_000_000_000;
+    let buy = curve.apply_buy(1_000_000_000).unwrap();
+
+    let vault_after = vault_before + buy.sol_amount;
+    assert!(curve.reconcile_reserves(0, vault_before, vault_after).is_ok());
+
+    //  a lamport the curve didn't account for
+    assert!(curve.reconcile_reserves(0, vault_before, vault_after + 1).is_err());
+    assert!(curve.reconcile_reserves(0, vault_before, vault_after - 1).is_err());
+
+    //  the vault can't hold less than this curve's reserves
+    assert!(curve.reconcile_reserves(1, 0, buy.sol_amount - 1).is_err());
+}
+
+#[test]
+fn team_fees_accrue_only_in_accrual_mode() {
+    let mut config = config();
+    assert!(!config.accrues_team_fees());
+    config.fee_mode = FEE_MODE_ACCRUE;
+    assert!(config.accrues_team_fees());
+
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    curve.accrue_team_fees(10_000_000).unwrap();
+    curve.accrue_team_fees(5_000_000).unwrap();
+    assert_eq!(curve.team_fees_accrued, 15_000_000);
+    assert!(curve.accrue_team_fees(u64::MAX).is_err());
+
+    //  the vault holds the accrued fees on top of the reserves, `swap` reconciles without them
+    let vault_before = 10_000_000_000;
+    let buy = curve.apply_buy(1_000_000_000).unwrap();
+    let vault_after = vault_before + buy.sol_amount + 10_000_000;
+    assert!(curve.reconcile_reserves(0, vault_before, vault_after - 10_000_000).is_ok());
+    assert!(curve.reconcile_reserves(0, vault_before, vault_after).is_err());
+}
+
+#[test]
+fn quote_to_graduate_is_the_smallest_completing_buy() {
+    let config = config();
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    curve.real_token_reserves = 793_100_000_000_000;
+    curve.graduation_target = 85_000_000_000;
+    curve.apply_buy(10_000_000_000).unwrap();
+
+    let quote = curve.quote_to_graduate(&config, 0).unwrap();
+    assert!(quote.net_lamports <= quote.buy_amount);
+
+    let buy = |amount: u64| {
+        let mut curve = curve.clone();
+        let adjusted_amount = curve.buy_amount_after_fees(&config, amount, 0).unwrap();
+        curve.apply_buy(adjusted_amount);
+        curve.ready_to_complete
+    };
+    assert!(buy(quote.buy_amount));
+    assert!(!buy(quote.buy_amount - 1));
+
+    curve.ready_to_complete = true;
+    assert!(curve.quote_to_graduate(&config, 0).is_none());
+}
+
+#[test]
+fn graduation_quote_matches_the_completing_buy() {
+    let config = config();
+    //  the tokens run out before the graduation target, so the last buy completes the curve
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    curve.real_token_reserves = 1_000_000_000_000;
+    curve.graduation_target = 85_000_000_000;
+    curve.snipe_tax_bps = 500;
+    curve.snipe_tax_slots = 10;
+
+    let quote = curve.quote_to_graduate(&config, 0).unwrap();
+    assert_eq!(quote.net_lamports, curve.get_sol_for_buy_tokens(curve.real_token_reserves).unwrap());
+
+    //  `complete_buy` sends the quoted amount through the same swap path
+    let mut executed = curve.clone();
+    let adjusted_amount = executed.buy_amount_after_fees(&config, quote.buy_amount, 0).unwrap();
+    let buy = executed.apply_buy(adjusted_amount).unwrap();
+    assert!(executed.ready_to_complete);
+    assert_eq!(buy.sol_amount, quote.net_lamports);
+    assert_eq!(buy.token_amount, curve.real_token_reserves);
+    assert_eq!(
+        curve.filled_buy_amount(&config, quote.buy_amount, buy.sol_amount, 0),
+        Some(quote.buy_amount)
+    );
+}
+
+#[test]
+fn last_buy_is_priced_from_the_live_reserves() {
+    let config = config();
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    curve.real_token_reserves = 1_000_000_000_000;
+    let before = curve.clone();
+
+    //  the 1% fee is on, the buy asks for far more than the tokens left
+    let amount = 50_000_000_000;
+    let adjusted_amount = curve.buy_amount_after_fees(&config, amount, 0).unwrap();
+    let buy = curve.apply_buy(adjusted_amount).unwrap();
+
+    assert_eq!(buy.token_amount, before.real_token_reserves);
+    assert_eq!(buy.sol_amount, before.get_sol_for_buy_tokens(before.real_token_reserves).unwrap());
+    assert!(before.get_tokens_for_buy_sol(buy.sol_amount).unwrap() >= buy.token_amount);
+    assert!(before.get_tokens_for_buy_sol(buy.sol_amount - 1).unwrap() < buy.token_amount);
+
+    assert_eq!(curve.real_token_reserves, 0);
+    assert_eq!(curve.real_sol_reserves, buy.sol_amount);
+    assert_eq!(curve.virtual_sol_reserves, before.virtual_sol_reserves + buy.sol_amount);
+    assert!(curve.ready_to_complete);
+
+    //  the buyer is charged the filled lamports plus the fee on them
+    let charged_amount = curve.filled_buy_amount(&config, amount, buy.sol_amount, 0).unwrap();
+    assert_eq!(charged_amount, buy.sol_amount + calculate_fee(charged_amount, 100).unwrap());
+}
+
+#[test]
+fn partially_filled_buy_pays_fees_on_the_filled_part_only() {
+    let config = config();
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    curve.real_token_reserves = 1_000_000_000_000;
+    let amount = 50_000_000_000;
+
+    let adjusted_amount = curve.buy_amount_after_fees(&config, amount, 0).unwrap();
+    let buy = curve.apply_buy(adjusted_amount).unwrap();
+    assert!(curve.ready_to_complete);
+    assert!(buy.sol_amount < adjusted_amount);
+
+    //  the debit is the filled lamports plus the 1% fee on them, the rest stays with the buyer
+    let charged_amount = curve.filled_buy_amount(&config, amount, buy.sol_amount, 0).unwrap();
+    assert_eq!(charged_amount, buy.sol_amount + calculate_fee(charged_amount, 100).unwrap());
+    assert!(charged_amount < amount);
+    assert!(curve.buy_amount_after_fees(&config, charged_amount - 1, 0).unwrap() < buy.sol_amount);
+
+    //  the anti-snipe tax is charged on the filled part the same way
+    curve.snipe_tax_bps = 500;
+    curve.snipe_tax_slots = 10;
+    let charged_amount = curve.filled_buy_amount(&config, amount, buy.sol_amount, 0).unwrap();
+    let snipe_tax = calculate_fee(charged_amount, 500).unwrap();
+    let fee = calculate_fee(charged_amount - snipe_tax, 100).unwrap();
+    assert_eq!(charged_amount, buy.sol_amount + snipe_tax + fee);
+    assert!(curve.buy_amount_after_fees(&config, charged_amount - 1, 0).unwrap() < buy.sol_amount);
+
+    //  a full fill needs the whole amount, up to the fee rounding. `swap` charges `amount` for it
+    curve.snipe_tax_bps = 0;
+    let full_fill = curve.filled_buy_amount(&config, amount, adjusted_amount, 0).unwrap();
+    assert_eq!(curve.buy_amount_after_fees(&config, full_fill, 0).unwrap(), adjusted_amount);
+    assert!(curve.buy_amount_after_fees(&config, full_fill - 1, 0).unwrap() < adjusted_amount);
+    assert_eq!(curve.filled_buy_amount(&config, amount, amount, 0), None);
+}
+
+#[test]
+fn exact_out_buy_is_bounded_by_the_max_input() {
+    let config = config();
+    let curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    let token_amount = 10_000_000_000_000;
+
+    //  the gross amount covers the curve lamports plus the 1% fee, one lamport less doesn't
+    let buy_amount = curve
+        .buy_amount_for_tokens(&config, token_amount, u64::MAX, 0)
+        .unwrap();
+    let adjusted_amount = curve.buy_amount_after_fees(&config, buy_amount, 0).unwrap();
+    assert!(curve.get_tokens_for_buy_sol(adjusted_amount).unwrap() >= token_amount);
+    let adjusted_amount = curve.buy_amount_after_fees(&config, buy_amount - 1, 0).unwrap();
+    assert!(curve.get_tokens_for_buy_sol(adjusted_amount).unwrap() < token_amount);
+
+    let quote = |token_amount: u64, max_input: u64| {
+        curve.buy_amount_for_tokens(&config, token_amount, max_input, 0)
+    };
+    assert_eq!(quote(token_amount, buy_amount).unwrap(), buy_amount);
+    assert_eq!(
+        quote(token_amount, buy_amount - 1).unwrap_err(),
+        Error::from(ContractError::SlippageExceeded)
+    );
+    assert_eq!(
+        quote(0, u64::MAX).unwrap_err(),
+        Error::from(ContractError::InvalidAmount)
+    );
+    assert_eq!(
+        quote(curve.real_token_reserves + 1, u64::MAX).unwrap_err(),
+        Error::from(ContractError::BuyFailed)
+    );
+}
+
+#[test]
+fn creator_keeps_part_of_the_dev_buy() {
+    let mut config = config();
+    config.creator_min_hold_bps = 5_000;
+    let creator = Pubkey::new_unique();
+    let mut curve = BondingCurve {
+        creator,
+        creator_dev_buy_tokens: 1_000_000,
+        ..Default::default()
+    };
+
+    assert!(curve.check_creator_hold(&config, &creator, 1_000_000, 500_000).is_ok());
+    assert!(curve.check_creator_hold(&config, &creator, 1_000_000, 500_001).is_err());
+
+    //  other sellers and graduated curves are not limited
+    assert!(curve.check_creator_hold(&config, &Pubkey::new_unique(), 1_000_000, 1_000_000).is_ok());
+    curve.is_completed = true;
+    assert!(curve.check_creator_hold(&config, &creator, 1_000_000, 1_000_000).is_ok());
+}
+
+#[test]
+fn metadata_uri_must_match_an_allowed_prefix() {
+    let mut config = config();
+    assert!(config.validate_metadata_uri("data:text/plain,hi").is_ok());
+
+    config.allowed_uri_prefixes = vec!["https://".to_string(), "ipfs://".to_string()];
+    assert!(config.validate_metadata_uri("https://example.com/token.json").is_ok());
+    assert!(config.validate_metadata_uri("ipfs://bafy").is_ok());
+    assert!(config.validate_metadata_uri("http://example.com/token.json").is_err());
+    assert!(config.validate_metadata_uri("").is_err());
+}
+
+#[test]
+fn sell_reflection_stays_in_the_reserves() {
+    let mut config = config();
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    let buy = curve.apply_buy(10_000_000_000).unwrap();
+    let token_amount = buy.token_amount / 2;
+
+    let plain = curve.clone().settle_sell(&config, token_amount, 0).unwrap();
+    assert_eq!(plain.reflected_amount, 0);
+
+    config.sell_reflection_bps = 500;
+    let mut reflected_curve = curve.clone();
+    let reflected = reflected_curve.settle_sell(&config, token_amount, 0).unwrap();
+    assert_eq!(reflected.fee_amount, plain.fee_amount);
+    assert_eq!(
+        reflected.seller_amount + reflected.reflected_amount,
+        plain.seller_amount
+    );
+    assert_eq!(curve.sell_reflection(&config, token_amount), reflected.reflected_amount);
+
+    //  the reflected lamports raise the price for the remaining holders
+    let mut plain_curve = curve.clone();
+    config.sell_reflection_bps = 0;
+    plain_curve.settle_sell(&config, token_amount, 0).unwrap();
+    assert_eq!(
+        reflected_curve.real_sol_reserves,
+        plain_curve.real_sol_reserves + reflected.reflected_amount
+    );
+    assert!(reflected_curve.virtual_sol_reserves > plain_curve.virtual_sol_reserves);
+}
+
+#[test]
+fn config_load_checked_decodes_stored_configs() {
+    let config = config();
+    let (key, other_owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut data = Vec::new();
+    config.try_serialize(&mut data).unwrap();
+    //  `configure` never shrinks the account, stale trailing bytes are ignored
+    data.extend_from_slice(&[7; 16]);
+
+    let mut lamports = 0;
+    let account = AccountInfo::new(
+        &key,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &pump_raydium::ID,
+        false,
+        0,
+    );
+    assert_eq!(Config::load_checked(&account).unwrap(), config);
+
+    let mut foreign = account.clone();
+    foreign.owner = &other_owner;
+    assert!(Config::load_checked(&foreign).is_err());
+
+    account.try_borrow_mut_data().unwrap()[0] ^= 1;
+    assert!(Config::load_checked(&account).is_err());
+}
+
+#[test]
+fn stored_authority_reads_legacy_and_current_configs() {
+    let config = config();
+    let mut data = Vec::new();
+    config.try_serialize(&mut data).unwrap();
+    assert_eq!(
+        Config::stored_authority(&data).unwrap(),
+        (CONFIG_VERSION, config.authority)
+    );
+
+    //  configs stored before the version field keep the authority at the same offset
+    let legacy = LegacyConfig {
+        authority: Pubkey::new_unique(),
+        migration_authority: Pubkey::new_from_array([CONFIG_VERSION; 32]),
+        team_wallet: Pubkey::new_unique(),
+        migration_wallet: Pubkey::new_unique(),
+        init_bonding_curve: 80.0,
+        platform_buy_fee: 1.0,
+        platform_sell_fee: 1.0,
+        platform_migration_fee: 1.0,
+        curve_limit: 85_000_000_000,
+        lamport_amount_config: AmountConfig::Range { min: None, max: None },
+        token_supply_config: AmountConfig::Range { min: None, max: None },
+        token_decimals_config: AmountConfig::Enum(vec![6]),
+        initial_virtual_token_reserves_config: 1_073_000_191_000_000,
+        initial_virtual_sol_reserves_config: 30_000_000_000,
+        initial_real_token_reserves_config: 793_100_000_000_000,
+        initial_raydium_token_reserves: 206_900_000_000_000,
+        initial_raydium_sol_amount: 79_000_000_000,
+        initialized: true,
+    };
+    let mut data = Config::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    assert_eq!(
+        Config::stored_authority(&data).unwrap(),
+        (LEGACY_CONFIG_VERSION, legacy.authority)
+    );
+
+    assert!(Config::stored_authority(&data[..40]).is_err());
+
+    //  `migrate_config` rewrites it in the current layout
+    let upgraded = legacy.clone().upgrade();
+    let mut data = Vec::new();
+    upgraded.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), upgraded.space());
+    assert_eq!(
+        Config::stored_authority(&data).unwrap(),
+        (CONFIG_VERSION, legacy.authority)
+    );
+    assert_eq!(upgraded.curve_limit, legacy.curve_limit);
+    assert!(upgraded.fee_override_config.validate("fee_override_bps", &0).is_err());
+}
+
+#[test]
+fn legacy_curves_upgrade_onto_their_own_vault() {
+    let config = config();
+    let legacy = LegacyBondingCurve {
+        token_mint: Pubkey::new_unique(),
+        creator: Pubkey::new_unique(),
+        init_lamport: 30_000_000_000,
+        token_total_supply: 1_000_000_000_000_000,
+        virtual_sol_reserves: 40_000_000_000,
+        virtual_token_reserves: 800_000_000_000_000,
+        real_sol_reserves: 10_000_000_000,
+        real_token_reserves: 600_000_000_000_000,
+        is_completed: false,
+    };
+
+    //  `migrate_curve_vault` tells the layouts apart by size
+    assert!(legacy.try_to_vec().unwrap().len() < std::mem::size_of::<BondingCurve>());
+
+    let curve = legacy.clone().upgrade(&config);
+    assert!(curve.uses_curve_vault);
+    assert!(!curve.is_completed && !curve.ready_to_complete);
+    assert_eq!(curve.graduation_target, config.curve_limit);
+    assert_eq!(curve.real_sol_reserves, legacy.real_sol_reserves);
+    assert_eq!(curve.real_token_reserves, legacy.real_token_reserves);
+    assert!(curve.ath_price_scaled > 0);
+
+    //  a completed one is left for `finalize` to complete and lock its pool deposit
+    let curve = LegacyBondingCurve {
+        is_completed: true,
+        ..legacy
+    }
+    .upgrade(&config);
+    assert!(!curve.is_completed && curve.ready_to_complete);
+    assert_eq!(curve.graduation_target, 10_000_000_000);
+}
+
+#[test]
+fn configure_keeps_the_stored_ceiling_and_counter() {
+    let mut stored_config = config();
+    stored_config.max_fee_basis_points = 200;
+    stored_config.pending_migrations = 3;
+    stored_config.active_curves = 5;
+
+    let mut new_config = config();
+    new_config.max_fee_basis_points = 150;
+    new_config.pending_migrations = 0;
+    new_config.prepare(Some(&stored_config)).unwrap();
+    assert_eq!(new_config.pending_migrations, 3);
+    assert_eq!(new_config.active_curves, 5);
+    assert_eq!(new_config.version, CONFIG_VERSION);
+
+    new_config.max_fee_basis_points = 0;
+    assert_eq!(
+        new_config.prepare(Some(&stored_config)).unwrap_err(),
+        Error::from(ContractError::FeeExceedsCeiling)
+    );
+    assert!(new_config.prepare(None).is_ok());
+}
+
+#[test]
+fn virtual_token_reserves_cover_the_real_ones() {
+    let mut config = config();
+    assert!(config.validate_token_reserves().is_ok());
+
+    config.initial_virtual_token_reserves_config = config.initial_real_token_reserves_config;
+    assert!(config.validate_token_reserves().is_ok());
+
+    config.initial_virtual_token_reserves_config -= 1;
+    assert!(config.validate_token_reserves().is_err());
+
+    //  zero virtual reserves can't price a trade, even with nothing real behind them
+    config.initial_real_token_reserves_config = 0;
+    config.initial_virtual_token_reserves_config = 0;
+    assert!(config.validate_token_reserves().is_err());
+}
+
+#[test]
+fn migration_backlog_holds_back_completions() {
+    let mut config = config();
+    config.record_completion();
+    config.record_completion();
+    assert!(config.check_migration_backlog().is_ok());
+
+    config.max_pending_migrations = 2;
+    assert!(config.check_migration_backlog().is_err());
+
+    config.record_migration();
+    assert_eq!(config.pending_migrations, 1);
+    assert!(config.check_migration_backlog().is_ok());
+
+    config.record_migration();
+    config.record_migration();
+    assert_eq!(config.pending_migrations, 0);
+}
+
+#[test]
+fn config_only_closes_without_live_curves() {
+    let mut config = config();
+    config.check_closable().unwrap();
+
+    config.record_launch();
+    assert_eq!(config.check_closable(), Err(ContractError::ConfigInUse.into()));
+
+    //  a completed curve still holds the config until it has migrated
+    config.record_completion();
+    assert_eq!(config.active_curves, 0);
+    assert_eq!(config.check_closable(), Err(ContractError::ConfigInUse.into()));
+
+    config.record_migration();
+    config.check_closable().unwrap();
+}
+
+#[test]
+fn loss_sell_grace_applies_below_the_average_entry() {
+    let mut config = config();
+    config.platform_sell_fee = 2.0;
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    let mut basis = CostBasis::default();
+
+    let buy = curve.apply_buy(10_000_000_000).unwrap();
+    basis.record_buy(10_100_000_000, buy.token_amount);
+    assert_eq!(basis.cost_of(buy.token_amount / 2), 5_050_000_000);
+
+    //  selling straight back returns less than the entry, but the grace is opt-in
+    let token_amount = buy.token_amount;
+    assert_eq!(curve.loss_sell_fee_bps(&config, &basis, token_amount), None);
+
+    config.loss_sell_grace = true;
+    assert_eq!(curve.loss_sell_fee_bps(&config, &basis, token_amount), Some(0));
+    config.loss_sell_fee_bps = 500;
+    assert_eq!(curve.loss_sell_fee_bps(&config, &basis, token_amount), Some(200));
+
+    //  tokens without a recorded entry can't claim a loss
+    assert_eq!(curve.loss_sell_fee_bps(&config, &basis, buy.token_amount + 1), None);
+
+    //  later buyers push the price above the entry
+    curve.apply_buy(50_000_000_000).unwrap();
+    assert_eq!(curve.loss_sell_fee_bps(&config, &basis, token_amount), None);
+
+    basis.record_sell(buy.token_amount / 2, 6_000_000_000);
+    assert_eq!(basis.tokens, buy.token_amount - buy.token_amount / 2);
+    assert_eq!(basis.lamports, 10_100_000_000 - 5_050_000_000);
+    assert_eq!(basis.realized_pnl, 950_000_000);
+}
+
+#[test]
+fn sells_are_capped_near_graduation() {
+    let mut config = config();
+    config.graduation_window_bps = 9_000;
+    config.sell_limit_near_graduation_bps = 500;
+
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    curve.graduation_target = 85_000_000_000;
+    let buy = curve.apply_buy(80_000_000_000).unwrap();
+    assert!(curve.progress_bps() >= 9_000);
+
+    //  small sells are fine, dumping the position is not
+    assert!(curve.check_sell_near_graduation(&config, buy.token_amount / 100).is_ok());
+    assert!(curve.check_sell_near_graduation(&config, buy.token_amount).is_err());
+
+    config.sell_limit_near_graduation_bps = 0;
+    assert!(curve.check_sell_near_graduation(&config, buy.token_amount).is_ok());
+
+    //  outside the window the cap doesn't apply
+    config.sell_limit_near_graduation_bps = 500;
+    config.graduation_window_bps = 10_000;
+    assert!(curve.check_sell_near_graduation(&config, buy.token_amount).is_ok());
+}
+
+#[test]
+fn holder_cap_only_counts_new_holders() {
+    let mut config = config();
+    config.max_holders = 2;
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+
+    let mut basis = CostBasis::default();
+    assert!(basis.is_new());
+    //  a wallet that only sold tokens it received elsewhere is still new
+    basis.record_sell(1_000, 1_000_000);
+    assert!(basis.is_new());
+
+    curve.record_new_holder(&config).unwrap();
+    curve.record_new_holder(&config).unwrap();
+    assert_eq!(curve.holder_count, 2);
+    assert!(curve.record_new_holder(&config).is_err());
+
+    //  positions are still tracked through the loss sell grace
+    config.max_holders = 0;
+    config.loss_sell_grace = true;
+    curve.record_new_holder(&config).unwrap();
+    assert_eq!(curve.holder_count, 3);
+
+    //  untracked buys never stamp a cost basis, so they are not counted
+    config.loss_sell_grace = false;
+    curve.record_new_holder(&config).unwrap();
+    assert_eq!(curve.holder_count, 3);
+}
+
+//  `swap` buys and `complete_buy` book positions through the same two steps
+#[test]
+fn trades_open_and_record_positions() {
+    let mut config = config();
+    config.max_holders = 1;
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    let (user, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    //  a tracked trade needs the wallet's cost basis
+    assert_eq!(
+        curve.open_position(&config, None, 0),
+        Err(ContractError::CostBasisRequired.into())
+    );
+
+    let mut basis = CostBasis::default();
+    curve.open_position(&config, Some(&basis), 0).unwrap();
+    assert_eq!(curve.holder_count, 1);
+    basis.record_trade(user, mint, 0, 1_000_000_000, 5_000_000, 1_010_000_000);
+    assert_eq!((basis.user, basis.mint), (user, mint));
+    assert_eq!((basis.tokens, basis.lamports), (5_000_000, 1_010_000_000));
+
+    //  the stamped wallet buys again without counting as a new holder
+    curve.open_position(&config, Some(&basis), 0).unwrap();
+    assert_eq!(curve.holder_count, 1);
+    //  a new wallet is held back by the cap, sells never are
+    let fresh = CostBasis::default();
+    assert!(curve.open_position(&config, Some(&fresh), 0).is_err());
+    curve.open_position(&config, Some(&fresh), 1).unwrap();
+
+    basis.record_trade(user, mint, 1, 2_500_000, 600_000_000, 0);
+    assert_eq!((basis.tokens, basis.lamports), (2_500_000, 505_000_000));
+    assert_eq!(basis.realized_pnl, 95_000_000);
+
+    //  untracked trades need no cost basis
+    config.max_holders = 0;
+    curve.open_position(&config, None, 0).unwrap();
+}
+
+#[test]
+fn migration_surplus_follows_the_config() {
+    let mut config = config();
+    config.initial_raydium_sol_amount = 80_000_000_000;
+    let amounts = MigrationAmounts {
+        sol_amount: 100_000_000_000,
+        token_amount: 200_000_000_000_000,
+        fee_amount: 1_000_000_000,
+        burn_amount: 0,
+        surplus_amount: 0,
+    };
+
+    let added = amounts.clone().settle_surplus(&config).unwrap();
+    assert_eq!(added.sol_amount, 100_000_000_000);
+    assert_eq!(added.surplus_amount, 0);
+
+    config.migration_surplus = MigrationSurplus::ReturnToTeam;
+    let returned = amounts.clone().settle_surplus(&config).unwrap();
+    assert_eq!(returned.sol_amount, 80_000_000_000);
+    assert_eq!(returned.surplus_amount, 20_000_000_000);
+    assert_eq!(returned.token_amount, amounts.token_amount);
+
+    config.migration_surplus = MigrationSurplus::Burn;
+    let burned = amounts.clone().settle_surplus(&config).unwrap();
+    assert_eq!(burned.sol_amount, 100_000_000_000);
+    assert_eq!(burned.burn_amount, 40_000_000_000_000);
+    assert_eq!(burned.token_amount, 160_000_000_000_000);
+
+    //  nothing to settle when the curve raised less than the pool deposit
+    config.initial_raydium_sol_amount = 120_000_000_000;
+    let short = amounts.clone().settle_surplus(&config).unwrap();
+    assert_eq!(short.burn_amount, 0);
+    assert_eq!(short.token_amount, amounts.token_amount);
+}
+
+//  `simulate_migrate` previews the plan `migrate` runs, surplus, unsold burn and lp lock included
+#[test]
+fn migration_plan_settles_the_surplus_and_the_unsold_burn() {
+    let mut config = config();
+    config.initial_raydium_sol_amount = 80_000_000_000;
+    config.initial_raydium_token_reserves = 150_000_000_000_000;
+    config.migration_surplus = MigrationSurplus::ReturnToTeam;
+    config.locked_lp_amount = 1_000;
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    curve.real_sol_reserves = 101_000_000_000;
+    curve.migration_sol_amount = 100_000_000_000;
+    curve.migration_token_amount = 200_000_000_000_000;
+
+    //  shared vault curves deposit everything they wrapped
+    let plan = curve.migration_plan(&config, true, 6).unwrap();
+    assert_eq!(plan.amounts.sol_amount, 100_000_000_000);
+    assert_eq!(plan.amounts.token_amount, 150_000_000_000_000);
+    assert_eq!(plan.amounts.burn_amount, 50_000_000_000_000);
+    assert_eq!(plan.unsold_burn_amount, 50_000_000_000_000);
+    assert_eq!(plan.lp_locked, 1_000);
+
+    curve.uses_curve_vault = true;
+    let plan = curve.migration_plan(&config, true, 6).unwrap();
+    assert_eq!(plan.amounts.sol_amount, 80_000_000_000);
+    assert_eq!(plan.amounts.surplus_amount, 20_000_000_000);
+    assert_eq!(plan.amounts.fee_amount, 1_000_000_000);
+    assert_eq!(plan.unsold_burn_amount, 50_000_000_000_000);
+
+    //  a lock the deposit can't cover fails the plan
+    config.locked_lp_amount = u64::MAX;
+    assert!(curve.migration_plan(&config, true, 6).is_err());
+}
+
+#[test]
+fn dust_buys_rejected_below_the_floor() {
+    let mut config = config();
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+
+    //  0 disables the floor
+    curve.check_min_buy(&config, 1).unwrap();
+
+    config.min_buy_lamports = 10_000_000;
+    assert!(curve.check_min_buy(&config, 9_999_999).is_err());
+    curve.check_min_buy(&config, 10_000_000).unwrap();
+
+    //  the completing buy only pays what's left to graduate
+    curve.ready_to_complete = true;
+    curve.check_min_buy(&config, 1).unwrap();
+}
+
+#[test]
+fn migration_waits_for_the_delay() {
+    let mut config = config();
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    curve.mark_completed_at(1_760_000_000);
+
+    //  0 keeps immediate migration
+    curve.check_migration_delay(&config, 1_760_000_000).unwrap();
+
+    config.migration_delay_seconds = 60;
+    assert!(curve.check_migration_delay(&config, 1_760_000_000).is_err());
+    assert!(curve.check_migration_delay(&config, 1_760_000_059).is_err());
+    curve.check_migration_delay(&config, 1_760_000_060).unwrap();
+
+    config.migration_delay_seconds = u64::MAX;
+    assert!(curve.check_migration_delay(&config, i64::MAX - 1).is_err());
+}
+
+#[test]
+fn trades_expire_after_their_deadline() {
+    assert!(check_deadline(None, i64::MAX).is_ok());
+    assert!(check_deadline(Some(1_000), 999).is_ok());
+    assert!(check_deadline(Some(1_000), 1_000).is_ok());
+    assert_eq!(
+        check_deadline(Some(1_000), 1_001).unwrap_err(),
+        Error::from(ContractError::TransactionExpired)
+    );
+}
+
+#[test]
+fn unknown_swap_directions_are_rejected() {
+    assert!(check_swap_direction(0, None).is_ok());
+    assert!(check_swap_direction(1, None).is_ok());
+    assert!(check_swap_direction(SWAP_BUY_EXACT_OUT, Some(1_000_000_000)).is_ok());
+    assert_eq!(
+        check_swap_direction(SWAP_BUY_EXACT_OUT, None).unwrap_err(),
+        Error::from(ContractError::InvalidAmount)
+    );
+    for direction in [3, 4, u8::MAX] {
+        assert_eq!(
+            check_swap_direction(direction, Some(1_000_000_000)).unwrap_err(),
+            Error::from(ContractError::InvalidDirection)
+        );
+    }
+}
+
+#[test]
+fn fee_ceiling_bounds_fees_and_cannot_be_raised() {
+    let mut config = config();
+    config.max_fee_basis_points = 200;
+    config.validate_fee_ceiling(0).unwrap();
+
+    //  the configured 1% fees fit, a 3% sell fee doesn't
+    config.platform_sell_fee = 3.0;
+    assert_eq!(
+        config.validate_fee_ceiling(0).unwrap_err(),
+        Error::from(ContractError::FeeExceedsCeiling)
+    );
+    config.platform_sell_fee = 1.0;
+
+    //  lowering is fine, raising or clearing a stored ceiling isn't
+    config.max_fee_basis_points = 150;
+    config.validate_fee_ceiling(200).unwrap();
+    config.max_fee_basis_points = 250;
+    assert!(config.validate_fee_ceiling(200).is_err());
+    config.max_fee_basis_points = 0;
+    assert!(config.validate_fee_ceiling(200).is_err());
+
+    //  per-curve overrides are bounded too
+    config.max_fee_basis_points = 200;
+    config.check_fee_ceiling("fee_override_bps", 200).unwrap();
+    assert!(config.check_fee_ceiling("fee_override_bps", 201).is_err());
+}
+
+#[test]
+fn ath_price_only_moves_up() {
+    let mut curve = curve(30_000_000_000, 1_073_000_191_000_000);
+    assert!(curve.record_ath_price());
+    let launch_price = curve.ath_price_scaled;
+    assert_eq!(launch_price, curve.spot_price_lamports_per_token_scaled(9));
+    assert!(!curve.record_ath_price());
+
+    let buy = curve.apply_buy(5_000_000_000).unwrap();
+    assert!(curve.record_ath_price());
+    let high = curve.ath_price_scaled;
+    assert!(high > launch_price);
+
+    //  selling back lowers the spot price but keeps the high
+    curve.apply_sell(buy.token_amount / 2).unwrap();
+    assert!(!curve.record_ath_price());
+    assert_eq!(curve.ath_price_scaled, high);
+}
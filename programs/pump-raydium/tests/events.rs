@@ -0,0 +1,249 @@
+//! Pins the wire layout of the events indexers decode
+
+use anchor_lang::{prelude::*, solana_program::hash::hash, Event};
+use pump_raydium::{
+    constants::SPOT_PRICE_SCALE,
+    events::{LaunchEvent, MigrationEvent, SwapEvent, TradeBreakdownEvent},
+    state::{
+        bondingcurve::{BondingCurve, MigrationAmounts},
+        launch_params::TokenLaunchParams,
+    },
+    utils::fee_breakdown,
+};
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], name: &str) -> Self {
+        let discriminator = hash(format!("event:{name}").as_bytes()).to_bytes();
+        assert_eq!(&data[..8], &discriminator[..8]);
+
+        Self { data, offset: 8 }
+    }
+
+    fn take(&mut self, len: usize) -> &'a [u8] {
+        let bytes = &self.data[self.offset..self.offset + len];
+        self.offset += len;
+        bytes
+    }
+
+    fn pubkey(&mut self) -> Pubkey {
+        Pubkey::try_from(self.take(32)).unwrap()
+    }
+
+    fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.take(8).try_into().unwrap())
+    }
+
+    fn i64(&mut self) -> i64 {
+        i64::from_le_bytes(self.take(8).try_into().unwrap())
+    }
+
+    fn u16(&mut self) -> u16 {
+        u16::from_le_bytes(self.take(2).try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> u8 {
+        self.take(1)[0]
+    }
+
+    fn bool(&mut self) -> bool {
+        self.u8() != 0
+    }
+
+    fn option_u64(&mut self) -> Option<u64> {
+        self.bool().then(|| self.u64())
+    }
+
+    fn finish(self) {
+        assert_eq!(self.offset, self.data.len());
+    }
+}
+
+#[test]
+fn launch_event_layout() {
+    let params = TokenLaunchParams {
+        decimals: 6,
+        token_supply: 1_000_000_000_000_000,
+        reserve_lamport: 20_000_000_000,
+        graduation_target: None,
+        initial_buy_lamports: None,
+        min_tokens_out: None,
+        team_allocation_bps: None,
+        verify_creator: false,
+        seller_fee_basis_points: Some(250),
+        fee_override_bps: None,
+        bootstrap_liquidity_bps: None,
+        is_mutable: None,
+        migration_target: None,
+        name: "Token".to_string(),
+        symbol: "TKN".to_string(),
+        uri: "https://example.com/token.json".to_string(),
+    };
+    let event = LaunchEvent::new(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        &params,
+        793_100_000_000_000,
+    );
+    assert_eq!(event.token_total_supply_ui, 1_000_000_000);
+    assert_eq!(event.seller_fee_basis_points, 250);
+    //  launches are immutable unless the creator opts in
+    assert!(!event.is_mutable);
+    let data = event.data();
+
+    let mut reader = Reader::new(&data, "LaunchEvent");
+    assert_eq!(reader.pubkey(), event.creator);
+    assert_eq!(reader.pubkey(), event.mint);
+    assert_eq!(reader.pubkey(), event.bonding_curve);
+    assert_eq!(reader.pubkey(), event.metadata);
+    assert_eq!(reader.u8(), params.decimals);
+    assert_eq!(reader.u64(), params.token_supply);
+    assert_eq!(reader.u64(), event.token_total_supply_ui);
+    assert_eq!(reader.u64(), params.reserve_lamport);
+    assert_eq!(reader.u64(), 793_100_000_000_000);
+    assert_eq!(reader.u16(), event.seller_fee_basis_points);
+    assert_eq!(reader.bool(), event.is_mutable);
+    reader.finish();
+}
+
+#[test]
+fn swap_event_layout() {
+    let mut curve = BondingCurve {
+        virtual_sol_reserves: 31_000_000_000,
+        virtual_token_reserves: 1_038_387_281_612_904,
+        snipe_tax_bps: 500,
+        snipe_tax_slots: 10,
+        launch_slot: 100,
+        ..Default::default()
+    };
+    let ath_price_before = curve.ath_price_scaled;
+    assert!(curve.record_ath_price());
+    let spot_price = curve.spot_price_lamports_per_token_scaled(SPOT_PRICE_SCALE);
+
+    let (user, mint, bonding_curve) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let event = SwapEvent::new(
+        user,
+        mint,
+        bonding_curve,
+        &curve,
+        1_000_000_000,
+        0,
+        30_000_000_000_000,
+        34_612_909_387_096,
+        105,
+        ath_price_before,
+    );
+    assert!(event.snipe_tax_applied);
+    assert_eq!(event.spot_price_scaled, spot_price);
+    assert_eq!(event.new_ath_price_scaled, Some(spot_price));
+
+    //  sells never pay the snipe tax, and a trade below the high leaves the ath unset
+    let sell = SwapEvent::new(
+        user,
+        mint,
+        bonding_curve,
+        &curve,
+        1_000,
+        1,
+        0,
+        29,
+        105,
+        spot_price,
+    );
+    assert!(!sell.snipe_tax_applied);
+    assert_eq!(sell.new_ath_price_scaled, None);
+    //  and the tax window closes after `snipe_tax_slots`
+    let late = SwapEvent::new(
+        user,
+        mint,
+        bonding_curve,
+        &curve,
+        1_000,
+        0,
+        0,
+        29,
+        110,
+        spot_price,
+    );
+    assert!(!late.snipe_tax_applied);
+
+    let data = event.data();
+    let mut reader = Reader::new(&data, "SwapEvent");
+    assert_eq!(reader.pubkey(), user);
+    assert_eq!(reader.pubkey(), mint);
+    assert_eq!(reader.pubkey(), bonding_curve);
+    assert_eq!(reader.u64(), 1_000_000_000);
+    assert_eq!(reader.u8(), 0);
+    assert_eq!(reader.u64(), 30_000_000_000_000);
+    assert_eq!(reader.u64(), 34_612_909_387_096);
+    assert_eq!(reader.u64(), curve.virtual_sol_reserves);
+    assert_eq!(reader.u64(), curve.virtual_token_reserves);
+    assert!(reader.bool());
+    assert_eq!(reader.u64(), spot_price);
+    assert_eq!(reader.option_u64(), Some(spot_price));
+    reader.finish();
+}
+
+#[test]
+fn migration_event_layout() {
+    let amounts = MigrationAmounts {
+        sol_amount: 79_000_000_000,
+        token_amount: 206_900_000_000_000,
+        fee_amount: 1_000_000_000,
+        burn_amount: 0,
+        surplus_amount: 0,
+    };
+    let event = MigrationEvent::new(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        5_477_225_575,
+        &amounts,
+        1_000,
+        1_760_000_000,
+    );
+    let data = event.data();
+
+    let mut reader = Reader::new(&data, "MigrationEvent");
+    assert_eq!(reader.pubkey(), event.mint);
+    assert_eq!(reader.pubkey(), event.pool);
+    assert_eq!(reader.u64(), 5_477_225_575);
+    assert_eq!(reader.u64(), amounts.sol_amount);
+    assert_eq!(reader.u64(), amounts.token_amount);
+    assert_eq!(reader.u64(), 1_000);
+    assert_eq!(reader.i64(), 1_760_000_000);
+    reader.finish();
+}
+
+#[test]
+fn trade_breakdown_event_layout() {
+    let fees = fee_breakdown(990_000_000, 10_000_000, 100).unwrap();
+    let event = TradeBreakdownEvent::new(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        1,
+        &fees,
+    );
+    let data = event.data();
+
+    let mut reader = Reader::new(&data, "TradeBreakdownEvent");
+    assert_eq!(reader.pubkey(), event.user);
+    assert_eq!(reader.pubkey(), event.mint);
+    assert_eq!(reader.pubkey(), event.bonding_curve);
+    assert_eq!(reader.u8(), 1);
+    assert_eq!(reader.u64(), 1_000_000_000);
+    assert_eq!(reader.u64(), 10_000_000);
+    assert_eq!(reader.u64(), 990_000_000);
+    assert_eq!(reader.u16(), 100);
+    reader.finish();
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use pump_raydium::{
+    errors::ContractError,
+    utils::{calculate_fee, fee_breakdown, percent_to_bps},
+};
+
+#[test]
+fn calculate_fee_rounds_down() {
+    assert_eq!(calculate_fee(0, 100).unwrap(), 0);
+    assert_eq!(calculate_fee(1_000_000_000, 0).unwrap(), 0);
+    assert_eq!(calculate_fee(1_000_000_000, 100).unwrap(), 10_000_000);
+    assert_eq!(calculate_fee(1_000_000_000, 10_000).unwrap(), 1_000_000_000);
+    assert_eq!(calculate_fee(9_999, 1).unwrap(), 0);
+    assert_eq!(calculate_fee(10_001, 1).unwrap(), 1);
+}
+
+#[test]
+fn calculate_fee_handles_large_amounts() {
+    assert_eq!(calculate_fee(u64::MAX, 10_000).unwrap(), u64::MAX);
+    assert_eq!(calculate_fee(u64::MAX, 5_000).unwrap(), u64::MAX / 2);
+    assert_eq!(
+        calculate_fee(u64::MAX, u16::MAX).unwrap_err(),
+        Error::from(ContractError::ValueInvalid)
+    );
+}
+
+#[test]
+fn percent_fees_convert_to_bps() {
+    assert_eq!(percent_to_bps(0.0), 0);
+    assert_eq!(percent_to_bps(1.0), 100);
+    assert_eq!(percent_to_bps(1.25), 125);
+    assert_eq!(percent_to_bps(100.0), 10_000);
+}
+
+#[test]
+fn fee_breakdown_adds_up() {
+    let fees = fee_breakdown(990_000_000, 10_000_000, 100).unwrap();
+    assert_eq!(fees.gross_amount, 1_000_000_000);
+    assert_eq!(fees.fee_amount, 10_000_000);
+    assert_eq!(fees.net_amount, 990_000_000);
+    assert_eq!(fees.fee_bps, 100);
+
+    assert_eq!(
+        fee_breakdown(u64::MAX, 1, 100).unwrap_err(),
+        Error::from(ContractError::ValueInvalid)
+    );
+}
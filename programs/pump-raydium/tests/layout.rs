@@ -0,0 +1,21 @@
+use anchor_lang::{AccountSerialize, Discriminator};
+use pump_raydium::{
+    constants::BONDING_CURVE_IS_COMPLETED_OFFSET, state::bondingcurve::BondingCurve,
+};
+
+#[test]
+fn is_completed_offset_is_stable() {
+    for is_completed in [false, true] {
+        let curve = BondingCurve {
+            is_completed,
+            virtual_sol_reserves: u64::MAX,
+            ..Default::default()
+        };
+
+        let mut data = Vec::new();
+        curve.try_serialize(&mut data).unwrap();
+
+        assert_eq!(&data[..8], &BondingCurve::DISCRIMINATOR);
+        assert_eq!(data[BONDING_CURVE_IS_COMPLETED_OFFSET], is_completed as u8);
+    }
+}
@@ -0,0 +1,213 @@
+use anchor_lang::{
+    prelude::{Error, Program},
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::Instruction,
+        program_error::ProgramError,
+        program_option::COption,
+        program_pack::Pack,
+        program_stubs::{set_syscall_stubs, SyscallStubs},
+        pubkey::Pubkey,
+    },
+};
+use anchor_spl::token::Token;
+use pump_raydium::{
+    constants::GLOBAL,
+    errors::ContractError,
+    pda::{global_token_account, global_vault_pda},
+    utils::{check_launch_mint, mint_with_signer, revoke_mint_authority},
+};
+use spl_token::{
+    error::TokenError,
+    processor::Processor,
+    state::{Account, AccountState, Mint},
+};
+use std::sync::Once;
+
+const TOKEN_SUPPLY: u64 = 1_000_000_000_000_000;
+
+//  routes the program's token CPIs into spl-token, signing for the PDAs the seeds derive
+struct TokenCpi;
+
+impl SyscallStubs for TokenCpi {
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        assert_eq!(instruction.program_id, spl_token::ID);
+        let signers = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &pump_raydium::ID))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(|meta| {
+                let mut account = account_infos
+                    .iter()
+                    .find(|account| *account.key == meta.pubkey)
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?
+                    .clone();
+                account.is_signer = account.is_signer || signers.contains(account.key);
+                Ok(account)
+            })
+            .collect::<Result<Vec<_>, ProgramError>>()?;
+
+        Processor::process(&instruction.program_id, &accounts, &instruction.data)
+    }
+}
+
+fn token_cpi() {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(TokenCpi));
+    });
+}
+
+fn mint_data(mint_authority: &Pubkey) -> Vec<u8> {
+    let mut data = vec![0; Mint::LEN];
+    Mint::pack(
+        Mint {
+            mint_authority: COption::Some(*mint_authority),
+            supply: 0,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        },
+        &mut data,
+    )
+    .unwrap();
+    data
+}
+
+fn token_account_data(mint: &Pubkey, owner: &Pubkey) -> Vec<u8> {
+    let mut data = vec![0; Account::LEN];
+    Account::pack(
+        Account {
+            mint: *mint,
+            owner: *owner,
+            state: AccountState::Initialized,
+            ..Default::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+    data
+}
+
+//  runs the mint lifecycle of `create_bonding_curve` through its own helpers: the global vault
+//  mints the supply, revokes its authority, and from then on can't mint again even with its seeds
+#[test]
+fn launch_revokes_the_mint_authority() {
+    token_cpi();
+    let (global_vault, global_vault_bump) = global_vault_pda();
+    let mint = Pubkey::new_unique();
+    let token_account = global_token_account(&mint);
+    let token_program_id = spl_token::ID;
+    let system_program = Pubkey::default();
+
+    let (mut mint_lamports, mut token_lamports, mut vault_lamports, mut program_lamports) =
+        (0, 0, 0, 0);
+    let mut mint_data = mint_data(&global_vault);
+    let mut token_data = token_account_data(&mint, &global_vault);
+    let mut vault_data: [u8; 0] = [];
+    let mut program_data: [u8; 0] = [];
+    let mint_info = AccountInfo::new(
+        &mint,
+        false,
+        true,
+        &mut mint_lamports,
+        &mut mint_data,
+        &spl_token::ID,
+        false,
+        0,
+    );
+    let token_info = AccountInfo::new(
+        &token_account,
+        false,
+        true,
+        &mut token_lamports,
+        &mut token_data,
+        &spl_token::ID,
+        false,
+        0,
+    );
+    //  the vault only signs through the seeds handed to the cpi
+    let vault_info = AccountInfo::new(
+        &global_vault,
+        false,
+        true,
+        &mut vault_lamports,
+        &mut vault_data,
+        &system_program,
+        false,
+        0,
+    );
+    let program_info = AccountInfo::new(
+        &token_program_id,
+        false,
+        false,
+        &mut program_lamports,
+        &mut program_data,
+        &system_program,
+        true,
+        0,
+    );
+    let token_program: Program<Token> = Program::try_from(&program_info).unwrap();
+    let signer_seeds: &[&[&[u8]]] = &[&[GLOBAL.as_bytes(), &[global_vault_bump]]];
+
+    mint_with_signer(
+        mint_info.clone(),
+        token_info.clone(),
+        vault_info.clone(),
+        &token_program,
+        signer_seeds,
+        TOKEN_SUPPLY,
+    )
+    .unwrap();
+    //  the supply is in place but the vault can still mint, the launch must not go out like this
+    assert_eq!(
+        check_launch_mint(&mint_info, TOKEN_SUPPLY).unwrap_err(),
+        Error::from(ContractError::MintAuthorityNotRevoked)
+    );
+
+    revoke_mint_authority(
+        mint_info.clone(),
+        vault_info.clone(),
+        &token_program,
+        signer_seeds,
+    )
+    .unwrap();
+    check_launch_mint(&mint_info, TOKEN_SUPPLY).unwrap();
+    assert_eq!(
+        check_launch_mint(&mint_info, TOKEN_SUPPLY - 1).unwrap_err(),
+        Error::from(ContractError::SupplyMismatch)
+    );
+
+    assert_eq!(
+        mint_with_signer(
+            mint_info.clone(),
+            token_info.clone(),
+            vault_info,
+            &token_program,
+            signer_seeds,
+            1,
+        )
+        .unwrap_err(),
+        Error::from(ProgramError::from(TokenError::FixedSupply))
+    );
+
+    let mint_state = Mint::unpack(&mint_info.try_borrow_data().unwrap()).unwrap();
+    assert_eq!(mint_state.mint_authority, COption::None);
+    assert_eq!(mint_state.supply, TOKEN_SUPPLY);
+    assert_eq!(
+        Account::unpack(&token_info.try_borrow_data().unwrap())
+            .unwrap()
+            .amount,
+        TOKEN_SUPPLY
+    );
+}
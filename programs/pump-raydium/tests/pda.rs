@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use pump_raydium::{errors::ContractError, pda::global_vault_pda, utils::validate_global_vault};
+
+fn check(address: Pubkey, bump: u8) -> Result<()> {
+    let (mut lamports, mut data) = (0, [0u8; 0]);
+    let owner = Pubkey::default();
+    let account = AccountInfo::new(
+        &address,
+        false,
+        true,
+        &mut lamports,
+        &mut data,
+        &owner,
+        false,
+        0,
+    );
+
+    validate_global_vault(&account, bump)
+}
+
+#[test]
+fn global_vault_matches_its_canonical_bump() {
+    let (global_vault, bump) = global_vault_pda();
+
+    assert!(check(global_vault, bump).is_ok());
+    assert_eq!(
+        check(Pubkey::new_unique(), bump).unwrap_err(),
+        Error::from(ContractError::InvalidGlobalVault)
+    );
+    //  a different bump derives a different address, or none at all
+    assert!(check(global_vault, bump.wrapping_sub(1)).is_err());
+}
@@ -0,0 +1,191 @@
+use anchor_lang::{prelude::*, solana_program::program_pack::Pack};
+use pump_raydium::{
+    errors::ContractError,
+    utils::{
+        check_raydium_pool, integer_sqrt, lp_account_balance, lp_to_lock, map_raydium_error,
+        pool_vault_amount, raydium_initial_lp,
+    },
+};
+use spl_token::state::{Account, AccountState};
+
+fn check(lamports: u64, data_len: usize, coin_amount: u64, pc_amount: u64) -> Result<()> {
+    let key = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (mut lamports, mut data) = (lamports, vec![0u8; data_len]);
+    let amm = AccountInfo::new(
+        &key,
+        false,
+        true,
+        &mut lamports,
+        &mut data,
+        &owner,
+        false,
+        0,
+    );
+
+    check_raydium_pool(&amm, coin_amount, pc_amount, 6)
+}
+
+#[test]
+fn integer_sqrt_floors() {
+    assert_eq!(integer_sqrt(0), 0);
+    assert_eq!(integer_sqrt(1), 1);
+    assert_eq!(integer_sqrt(15), 3);
+    assert_eq!(integer_sqrt(16), 4);
+    assert_eq!(
+        integer_sqrt(u64::MAX as u128 * u64::MAX as u128),
+        u64::MAX as u128
+    );
+}
+
+#[test]
+fn initial_lp_keeps_the_locked_share() {
+    //  sqrt(4e12 * 1e12) = 2e12, raydium locks 1e6 of it
+    assert_eq!(
+        raydium_initial_lp(4_000_000_000_000, 1_000_000_000_000, 6),
+        Some(2_000_000_000_000 - 1_000_000)
+    );
+    assert_eq!(raydium_initial_lp(1_000_000, 1_000_000, 6), None);
+    assert_eq!(raydium_initial_lp(0, 1_000_000_000_000, 6), None);
+}
+
+#[test]
+fn locked_lp_comes_out_of_the_minted_lp() {
+    //  the deposit above mints 2e12 - 1e6 LP to the global vault
+    assert_eq!(lp_to_lock(0, 1_000, 1_000, 6), Ok(0));
+    assert_eq!(lp_to_lock(1_000, 4_000_000_000_000, 1_000_000_000_000, 6), Ok(1_000));
+    assert_eq!(
+        lp_to_lock(2_000_000_000_000 - 1_000_000, 4_000_000_000_000, 1_000_000_000_000, 6),
+        Ok(2_000_000_000_000 - 1_000_000)
+    );
+    assert_eq!(
+        lp_to_lock(2_000_000_000_000, 4_000_000_000_000, 1_000_000_000_000, 6),
+        Err(ContractError::InsufficientLpToLock.into())
+    );
+    //  a deposit too small to mint any LP can't lock any
+    assert_eq!(
+        lp_to_lock(1, 1_000_000, 1_000_000, 6),
+        Err(ContractError::InsufficientLpToLock.into())
+    );
+}
+
+#[test]
+fn existing_pool_is_reported() {
+    assert_eq!(
+        check(1_000_000, 752, 4_000_000_000_000, 1_000_000_000_000),
+        Err(ContractError::PoolAlreadyExists.into())
+    );
+}
+
+#[test]
+fn dust_migration_is_reported() {
+    assert_eq!(
+        check(0, 0, 1_000, 1_000),
+        Err(ContractError::MigrationAmountTooLow.into())
+    );
+    assert!(check(0, 0, 4_000_000_000_000, 1_000_000_000_000).is_ok());
+}
+
+#[test]
+fn cpi_errors_map_to_crate_errors() {
+    assert_eq!(
+        Error::from(map_raydium_error(ProgramError::AccountAlreadyInitialized)),
+        Error::from(ContractError::PoolAlreadyExists)
+    );
+    assert_eq!(
+        Error::from(map_raydium_error(ProgramError::InsufficientFunds)),
+        Error::from(ContractError::MigrationAmountTooLow)
+    );
+    assert_eq!(
+        Error::from(map_raydium_error(ProgramError::Custom(30))),
+        Error::from(ContractError::RaydiumCpiFailed)
+    );
+}
+
+fn token_account_data(mint: &Pubkey, authority: &Pubkey, amount: u64) -> Vec<u8> {
+    let mut data = vec![0; Account::LEN];
+    Account::pack(
+        Account {
+            mint: *mint,
+            owner: *authority,
+            amount,
+            state: AccountState::Initialized,
+            ..Default::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+    data
+}
+
+fn vault_amount(owner: &Pubkey, vault_mint: &Pubkey, mint: &Pubkey, amount: u64) -> Result<u64> {
+    let key = Pubkey::new_unique();
+    let mut lamports = 0;
+    let mut data = token_account_data(vault_mint, &Pubkey::new_unique(), amount);
+    let vault = AccountInfo::new(&key, false, true, &mut lamports, &mut data, owner, false, 0);
+
+    pool_vault_amount(&vault, mint)
+}
+
+fn lp_balance(
+    mut data: Vec<u8>,
+    owner: &Pubkey,
+    lp_mint: &Pubkey,
+    authority: &Pubkey,
+) -> Result<u64> {
+    let key = Pubkey::new_unique();
+    let mut lamports = 0;
+    let lp_account =
+        AccountInfo::new(&key, false, true, &mut lamports, &mut data, owner, false, 0);
+
+    lp_account_balance(&lp_account, lp_mint, authority)
+}
+
+#[test]
+fn pool_vaults_are_read_only_from_token_accounts_of_their_mint() {
+    let mint = Pubkey::new_unique();
+    assert_eq!(vault_amount(&spl_token::ID, &mint, &mint, 79_000_000_000), Ok(79_000_000_000));
+    //  same bytes under another owner, or a vault of another mint, don't count as the deposit
+    assert_eq!(
+        vault_amount(&Pubkey::new_unique(), &mint, &mint, 79_000_000_000),
+        Err(ContractError::RaydiumCpiFailed.into())
+    );
+    assert_eq!(
+        vault_amount(&spl_token::ID, &Pubkey::new_unique(), &mint, 79_000_000_000),
+        Err(ContractError::RaydiumCpiFailed.into())
+    );
+}
+
+#[test]
+fn lp_is_only_burned_from_the_global_vaults_lp_account() {
+    let (lp_mint, global_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+    //  before `initialize2` the account doesn't exist yet
+    assert_eq!(lp_balance(vec![], &Pubkey::default(), &lp_mint, &global_vault), Ok(0));
+    assert_eq!(
+        lp_balance(
+            token_account_data(&lp_mint, &global_vault, 1_000),
+            &spl_token::ID,
+            &lp_mint,
+            &global_vault
+        ),
+        Ok(1_000)
+    );
+    assert_eq!(
+        lp_balance(
+            token_account_data(&Pubkey::new_unique(), &global_vault, 1_000),
+            &spl_token::ID,
+            &lp_mint,
+            &global_vault
+        ),
+        Err(ContractError::InvalidTokenAccount.into())
+    );
+    assert_eq!(
+        lp_balance(
+            token_account_data(&lp_mint, &Pubkey::new_unique(), 1_000),
+            &spl_token::ID,
+            &lp_mint,
+            &global_vault
+        ),
+        Err(ContractError::InvalidTokenAccount.into())
+    );
+}
@@ -0,0 +1,138 @@
+use anchor_lang::{
+    prelude::{Program, Signer},
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::Instruction,
+        program_error::ProgramError,
+        program_option::COption,
+        program_pack::Pack,
+        program_stubs::{set_syscall_stubs, SyscallStubs},
+        pubkey::Pubkey,
+    },
+};
+use anchor_spl::token::Token;
+use pump_raydium::{pda::curve_vault_pda, utils::unwrap_user_wsol};
+use spl_token::{
+    native_mint,
+    processor::Processor,
+    state::{Account, AccountState},
+};
+
+const WSOL_RENT: u64 = 2_039_280;
+
+//  routes the program's token CPIs into spl-token, the user signs the close themselves
+struct TokenCpi;
+
+impl SyscallStubs for TokenCpi {
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        assert_eq!(instruction.program_id, spl_token::ID);
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(|meta| {
+                account_infos
+                    .iter()
+                    .find(|account| *account.key == meta.pubkey)
+                    .cloned()
+                    .ok_or(ProgramError::NotEnoughAccountKeys)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Processor::process(&instruction.program_id, &accounts, &instruction.data)
+    }
+}
+
+fn wsol_account_data(owner: &Pubkey, amount: u64) -> Vec<u8> {
+    let mut data = vec![0; Account::LEN];
+    Account::pack(
+        Account {
+            mint: native_mint::ID,
+            owner: *owner,
+            amount,
+            state: AccountState::Initialized,
+            is_native: COption::Some(WSOL_RENT),
+            ..Default::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+    data
+}
+
+//  a WSOL buy moves the whole WSOL account, balance and rent, into the curve's own vault
+//  and nothing out of any other account
+#[test]
+fn wsol_buys_unwrap_into_the_curve_vault() {
+    set_syscall_stubs(Box::new(TokenCpi));
+    let user = Pubkey::new_unique();
+    let user_wsol = Pubkey::new_unique();
+    let (curve_vault, _) = curve_vault_pda(&Pubkey::new_unique());
+    let token_program_id = spl_token::ID;
+    let system_program = Pubkey::default();
+    //  spl-token hands a closed account to the system program by writing its owner in place
+    let wsol_owner = spl_token::ID;
+
+    let wsol_amount = 1_500_000_000;
+    let vault_reserves = 40_000_000_000;
+    let (mut user_lamports, mut wsol_lamports, mut vault_lamports, mut program_lamports) =
+        (10_000_000, WSOL_RENT + wsol_amount, vault_reserves, 0);
+    let mut wsol_data = wsol_account_data(&user, wsol_amount);
+    let (mut user_data, mut vault_data, mut program_data): ([u8; 0], [u8; 0], [u8; 0]) =
+        ([], [], []);
+    let user_info = AccountInfo::new(
+        &user,
+        true,
+        true,
+        &mut user_lamports,
+        &mut user_data,
+        &system_program,
+        false,
+        0,
+    );
+    let wsol_info = AccountInfo::new(
+        &user_wsol,
+        false,
+        true,
+        &mut wsol_lamports,
+        &mut wsol_data,
+        &wsol_owner,
+        false,
+        0,
+    );
+    let vault_info = AccountInfo::new(
+        &curve_vault,
+        false,
+        true,
+        &mut vault_lamports,
+        &mut vault_data,
+        &system_program,
+        false,
+        0,
+    );
+    let program_info = AccountInfo::new(
+        &token_program_id,
+        false,
+        false,
+        &mut program_lamports,
+        &mut program_data,
+        &system_program,
+        true,
+        0,
+    );
+    let token_program: Program<Token> = Program::try_from(&program_info).unwrap();
+    let signer = Signer::try_from(&user_info).unwrap();
+
+    let prepaid =
+        unwrap_user_wsol(wsol_info.clone(), vault_info.clone(), &signer, &token_program).unwrap();
+    assert_eq!(prepaid, WSOL_RENT + wsol_amount);
+    assert_eq!(vault_info.lamports(), vault_reserves + prepaid);
+    assert_eq!(wsol_info.lamports(), 0);
+    assert_eq!(*wsol_info.owner, system_program);
+    assert_eq!(user_info.lamports(), 10_000_000);
+}